@@ -4,6 +4,8 @@ import {
   addressToScVal,
   stringToScVal,
   numberToScVal,
+  u32ToScVal,
+  boolToScVal,
   bigIntToNumber,
 } from './stellar';
 import { supabase } from './supabase';
@@ -20,11 +22,14 @@ export interface CompanyInfo {
   description: string;
   token_price: number;
   target_amount: number;
+  clawback_enabled: boolean;
+  price_tick: number;
+  decimals: number;
 }
 
 /**
  * Initialize a new company token on-chain
- * Contract signature: init_company(env, name, symbol, total_supply, owner_addr, equity_percent, description, token_price, target_amount)
+ * Contract signature: init_company(env, name, symbol, total_supply, owner_addr, equity_percent, description, token_price, target_amount, clawback_enabled, decimals)
  */
 export async function initializeCompany(
   walletAddress: string,
@@ -35,7 +40,8 @@ export async function initializeCompany(
   equityPercent: number,
   description: string,
   tokenPrice: number,
-  targetAmount: number
+  targetAmount: number,
+  decimals: number = 7
 ): Promise<void> {
   // Convert to stroops (1 XLM = 10^7 stroops) for prices/amounts
   const tokenPriceStroops = Math.floor(tokenPrice * 10_000_000);
@@ -50,6 +56,8 @@ export async function initializeCompany(
     stringToScVal(description),
     numberToScVal(tokenPriceStroops),
     numberToScVal(targetAmountStroops),
+    boolToScVal(false), // clawback_enabled
+    u32ToScVal(decimals),
   ];
   
   console.log('Initializing company with params:', {
@@ -61,6 +69,7 @@ export async function initializeCompany(
     description,
     tokenPrice: `${tokenPrice} XLM (${tokenPriceStroops} stroops)`,
     targetAmount: `${targetAmount} XLM (${targetAmountStroops} stroops)`,
+    decimals,
   });
   
   await invokeContract(walletAddress, contractId, 'init_company', params);
@@ -84,6 +93,9 @@ export async function getCompanyInfo(contractId: string): Promise<CompanyInfo |
       description: result.description || '',
       token_price: bigIntToNumber(result.token_price),
       target_amount: bigIntToNumber(result.target_amount),
+      clawback_enabled: Boolean(result.clawback_enabled),
+      price_tick: bigIntToNumber(result.price_tick),
+      decimals: Number(result.decimals),
     };
   } catch (error) {
     console.error('Error getting company info:', error);
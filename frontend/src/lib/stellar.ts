@@ -158,6 +158,14 @@ export function numberToScVal(num: number): xdr.ScVal {
   return nativeToScVal(num, { type: 'i128' });
 }
 
+export function u32ToScVal(num: number): xdr.ScVal {
+  return nativeToScVal(num, { type: 'u32' });
+}
+
+export function boolToScVal(value: boolean): xdr.ScVal {
+  return nativeToScVal(value, { type: 'bool' });
+}
+
 /**
  * Convert BigInt to Number safely
  */
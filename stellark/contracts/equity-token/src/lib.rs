@@ -1,5 +1,12 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, contractevent, token, Address, Env, Symbol, String};
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, contractevent, panic_with_error, token,
+    Address, BytesN, Env, Symbol, String, Vec,
+};
+
+const TRANSFER_WINDOW: u64 = 86_400; // Transfer limits apply per day
+const MAX_DECIMALS: u32 = 18;
+const DIVIDEND_SCALE: i128 = 1_000_000_000_000; // Fixed-point scale of dividend per token
 
 #[contract]
 pub struct EquityToken;
@@ -18,6 +25,45 @@ pub struct CompanyInfo {
     pub description: String,
     pub token_price: i128,
     pub target_amount: i128,
+    pub clawback_enabled: bool,
+    pub price_tick: i128, // Resale prices must be a multiple of this (0 = any price)
+    pub decimals: u32,    // Display decimals for wallets (SEP-41)
+}
+
+// -----------------------------
+// 🤝 Allowance
+// -----------------------------
+#[derive(Clone)]
+#[contracttype]
+pub struct AllowanceValue {
+    pub amount: i128,
+    pub expiration_ledger: u32,
+}
+
+// -----------------------------
+// ❌ Error Codes
+// -----------------------------
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AlreadyInitialized = 1,
+    InvalidTotalSupply = 2,
+    InvalidEquityPercent = 3,
+    InvalidTokenPrice = 4,
+    InvalidTargetAmount = 5,
+    ClawbackDisabled = 6,
+    InsufficientBalance = 7,
+    NotMinter = 8,
+    TransferLimitExceeded = 9,
+    NotBurner = 10,
+    Overflow = 11,
+    InvalidPriceTick = 12,
+    InvalidDecimals = 13,
+    AccountFrozen = 14,
+    NoDividend = 15,
+    InvalidAmount = 16,
+    DividendTokenMismatch = 17,
 }
 
 // -----------------------------
@@ -51,6 +97,82 @@ pub struct BurnEvent {
     pub amount: i128,
 }
 
+#[contractevent]
+pub struct ClawbackEvent {
+    pub from: Address,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct ApproveEvent {
+    pub from: Address,
+    pub spender: Address,
+    pub amount: i128,
+    pub expiration_ledger: u32,
+}
+
+#[contractevent]
+pub struct MarketplaceSetEvent {
+    pub marketplace: Address,
+}
+
+#[contractevent]
+pub struct MinterSetEvent {
+    pub minter: Address,
+}
+
+#[contractevent]
+pub struct BurnerSetEvent {
+    pub burner: Address,
+}
+
+#[contractevent]
+pub struct DividendDistributedEvent {
+    pub round: u32,
+    pub xlm_token: Address,
+    pub total_amount: i128,
+}
+
+#[contractevent]
+pub struct DividendClaimedEvent {
+    pub holder: Address,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct FrozenEvent {
+    pub addr: Address,
+    pub frozen: bool,
+}
+
+#[contractevent]
+pub struct LimitExemptEvent {
+    pub addr: Address,
+    pub exempt: bool,
+}
+
+#[contractevent]
+pub struct UpgradedEvent {
+    pub new_wasm_hash: BytesN<32>,
+}
+
+#[contractevent]
+pub struct PriceTickSetEvent {
+    pub price_tick: i128,
+}
+
+#[contractevent]
+pub struct TransferLimitSetEvent {
+    pub limit: i128,
+}
+
+#[contractevent]
+pub struct MetadataUpdatedEvent {
+    pub name: String,
+    pub symbol: String,
+    pub description: String,
+}
+
 // -----------------------------
 // ⚙️ Contract Implementation
 // -----------------------------
@@ -67,9 +189,30 @@ impl EquityToken {
         description: String,
         token_price: i128,
         target_amount: i128,
-    ) {
+        clawback_enabled: bool,
+        decimals: u32,
+    ) -> Result<(), Error> {
         if env.storage().instance().has(&Symbol::new(&env, "initialized")) {
-            panic!("Already initialized");
+            return Err(Error::AlreadyInitialized);
+        }
+
+        // Tokenomics sanity checks
+        if total_supply <= 0 {
+            return Err(Error::InvalidTotalSupply);
+        }
+        if equity_percent <= 0 || equity_percent > 100 {
+            return Err(Error::InvalidEquityPercent);
+        }
+        if token_price <= 0 {
+            return Err(Error::InvalidTokenPrice);
+        }
+        // The target can't exceed what selling the whole supply would raise
+        let max_raise = total_supply.checked_mul(token_price).unwrap_or(i128::MAX);
+        if target_amount <= 0 || target_amount > max_raise {
+            return Err(Error::InvalidTargetAmount);
+        }
+        if decimals > MAX_DECIMALS {
+            return Err(Error::InvalidDecimals);
         }
 
         // Clone for event
@@ -87,10 +230,13 @@ impl EquityToken {
                 description,
                 token_price,
                 target_amount,
+                clawback_enabled,
+                price_tick: 0,
+                decimals,
             },
         );
 
-        env.storage().persistent().set(&owner_addr, &total_supply);
+        Self::write_balance(&env, &owner_addr, total_supply);
         env.storage().instance().set(&Symbol::new(&env, "initialized"), &true);
 
         // ✅ Emit event using macro’s auto `.publish()`
@@ -102,9 +248,13 @@ impl EquityToken {
             equity_percent,
         }
         .publish(&env);
+
+        Ok(())
     }
 
     // --- Mint tokens (buyer purchases from owner) ---
+    // Not a true mint: this is a sale from the owner's treasury, which holds the whole
+    // supply from init. total_supply is fixed there and only ever lowered by burns.
     // Buyer signs the transaction and receives tokens from owner's balance
     // XLM token address must be provided for payment
     pub fn mint(env: Env, to: Address, amount: i128, xlm_token: Address) {
@@ -118,56 +268,332 @@ impl EquityToken {
             .unwrap();
 
         let owner = company.owner.clone();
+        Self::require_not_frozen(&env, &owner);
+        Self::require_not_frozen(&env, &to);
 
-        // Get balances
-        let mut owner_balance: i128 = env.storage().persistent().get(&owner).unwrap_or(0);
-        let mut buyer_balance: i128 = env.storage().persistent().get(&to).unwrap_or(0);
+        if amount <= 0 {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
 
         // Check if owner has enough tokens
-        if owner_balance < amount {
+        if Self::balance_of(env.clone(), owner.clone()) < amount {
             panic!("Not enough tokens available for purchase");
         }
 
         // Calculate payment amount (token_price is in stroops)
-        let payment_amount = amount * company.token_price;
+        let payment_amount = amount
+            .checked_mul(company.token_price)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::Overflow));
 
         // Transfer XLM from buyer to company owner
         let xlm_client = token::Client::new(&env, &xlm_token);
         xlm_client.transfer(&to, &owner, &payment_amount);
 
         // Transfer equity tokens from owner to buyer (no supply inflation)
-        owner_balance -= amount;
-        buyer_balance += amount;
-
-        // Save updated balances
-        env.storage().persistent().set(&owner, &owner_balance);
-        env.storage().persistent().set(&to, &buyer_balance);
+        Self::move_balance(&env, &owner, &to, amount).unwrap_or_else(|e| panic_with_error!(&env, e));
 
         // ✅ Emit typed event
         MintEvent { to, amount }.publish(&env);
     }
 
+    // --- Register a delegated minter (owner only) ---
+    pub fn set_minter(env: Env, minter: Address) {
+        let company: CompanyInfo = env
+            .storage()
+            .instance()
+            .get(&Symbol::new(&env, "company_info"))
+            .unwrap();
+
+        company.owner.require_auth();
+
+        env.storage().instance().set(&Symbol::new(&env, "minter"), &minter);
+
+        // ✅ Typed event
+        MinterSetEvent { minter }.publish(&env);
+    }
+
+    // --- Register a delegated burner, e.g. the fundraising contract (owner only) ---
+    pub fn set_burner(env: Env, burner: Address) {
+        let company: CompanyInfo = env
+            .storage()
+            .instance()
+            .get(&Symbol::new(&env, "company_info"))
+            .unwrap();
+
+        company.owner.require_auth();
+
+        env.storage().instance().set(&Symbol::new(&env, "burner"), &burner);
+
+        // ✅ Typed event
+        BurnerSetEvent { burner }.publish(&env);
+    }
+
+    // --- Registered burner, if any ---
+    pub fn get_burner(env: Env) -> Option<Address> {
+        env.storage().instance().get(&Symbol::new(&env, "burner"))
+    }
+
+    // --- Freeze or unfreeze a holder, e.g. for a court order (owner only) ---
+    // Frozen accounts can't send, receive, mint or burn; clawback still applies
+    pub fn set_frozen(env: Env, addr: Address, frozen: bool) {
+        let company: CompanyInfo = env
+            .storage()
+            .instance()
+            .get(&Symbol::new(&env, "company_info"))
+            .unwrap();
+
+        company.owner.require_auth();
+
+        let key = (Symbol::new(&env, "frozen"), addr.clone());
+        if frozen {
+            env.storage().persistent().set(&key, &true);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+
+        // ✅ Typed event
+        FrozenEvent { addr, frozen }.publish(&env);
+    }
+
+    // --- Whether a holder is frozen ---
+    pub fn is_frozen(env: Env, addr: Address) -> bool {
+        env.storage()
+            .persistent()
+            .get(&(Symbol::new(&env, "frozen"), addr))
+            .unwrap_or(false)
+    }
+
+    // --- Cap what each holder may transfer per day (owner only, 0 = no limit) ---
+    pub fn set_transfer_limit(env: Env, limit: i128) {
+        let company: CompanyInfo = env
+            .storage()
+            .instance()
+            .get(&Symbol::new(&env, "company_info"))
+            .unwrap();
+
+        company.owner.require_auth();
+
+        if limit < 0 {
+            panic!("Invalid transfer limit");
+        }
+
+        env.storage().instance().set(&Symbol::new(&env, "transfer_limit"), &limit);
+
+        // ✅ Typed event
+        TransferLimitSetEvent { limit }.publish(&env);
+    }
+
+    // --- Exempt an address from the daily transfer limit (owner only) ---
+    // For contracts paying out supply on the company's behalf, e.g. a fundraising escrow
+    pub fn set_limit_exempt(env: Env, addr: Address, exempt: bool) {
+        let company: CompanyInfo = env
+            .storage()
+            .instance()
+            .get(&Symbol::new(&env, "company_info"))
+            .unwrap();
+
+        company.owner.require_auth();
+
+        let key = (Symbol::new(&env, "limit_exempt"), addr.clone());
+        if exempt {
+            env.storage().persistent().set(&key, &true);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+
+        // ✅ Typed event
+        LimitExemptEvent { addr, exempt }.publish(&env);
+    }
+
+    // --- Whether an address is exempt from the daily transfer limit ---
+    pub fn is_limit_exempt(env: Env, addr: Address) -> bool {
+        env.storage()
+            .persistent()
+            .get(&(Symbol::new(&env, "limit_exempt"), addr))
+            .unwrap_or(false)
+    }
+
+    // --- Require resale prices to be a multiple of `price_tick` (owner only, 0 = any price) ---
+    pub fn set_price_tick(env: Env, price_tick: i128) {
+        let mut company: CompanyInfo = env
+            .storage()
+            .instance()
+            .get(&Symbol::new(&env, "company_info"))
+            .unwrap();
+
+        company.owner.require_auth();
+
+        if price_tick < 0 {
+            panic_with_error!(&env, Error::InvalidPriceTick);
+        }
+
+        company.price_tick = price_tick;
+        env.storage().instance().set(&Symbol::new(&env, "company_info"), &company);
+
+        // ✅ Typed event
+        PriceTickSetEvent { price_tick }.publish(&env);
+    }
+
+    // --- Minimum resale price increment (0 = any price) ---
+    pub fn get_price_tick(env: Env) -> i128 {
+        Self::get_company_info(env).price_tick
+    }
+
+    // --- Per-holder daily transfer cap (0 = no limit) ---
+    pub fn get_transfer_limit(env: Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&Symbol::new(&env, "transfer_limit"))
+            .unwrap_or(0)
+    }
+
+    // --- Registered minter, if any ---
+    pub fn get_minter(env: Env) -> Option<Address> {
+        env.storage().instance().get(&Symbol::new(&env, "minter"))
+    }
+
+    // --- Issue tokens from owner's balance (registered minter signs, no payment) ---
+    pub fn mint_to(env: Env, minter: Address, to: Address, amount: i128) -> Result<(), Error> {
+        minter.require_auth();
+
+        let registered: Option<Address> = env.storage().instance().get(&Symbol::new(&env, "minter"));
+        if registered != Some(minter) {
+            return Err(Error::NotMinter);
+        }
+
+        let company: CompanyInfo = env
+            .storage()
+            .instance()
+            .get(&Symbol::new(&env, "company_info"))
+            .unwrap();
+
+        if Self::is_frozen(env.clone(), company.owner.clone()) || Self::is_frozen(env.clone(), to.clone()) {
+            return Err(Error::AccountFrozen);
+        }
+
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        Self::move_balance(&env, &company.owner, &to, amount)?;
+
+        // ✅ Typed event
+        MintEvent { to, amount }.publish(&env);
+
+        Ok(())
+    }
+
     // --- Transfer tokens (free - no payment) ---
     pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
         from.require_auth();
+        Self::require_not_frozen(&env, &from);
+        Self::require_not_frozen(&env, &to);
 
-        let mut from_balance: i128 = env.storage().persistent().get(&from).unwrap_or(0);
-        let mut to_balance: i128 = env.storage().persistent().get(&to).unwrap_or(0);
+        Self::move_balance(&env, &from, &to, amount).unwrap_or_else(|e| panic_with_error!(&env, e));
 
-        if from_balance < amount {
-            panic!("Insufficient balance");
+        // ✅ Typed event
+        TransferEvent { from, to, amount }.publish(&env);
+    }
+
+    // --- Approve a spender (SEP-41 allowance) ---
+    pub fn approve(env: Env, from: Address, spender: Address, amount: i128, expiration_ledger: u32) {
+        from.require_auth();
+
+        if amount < 0 {
+            panic!("Invalid amount");
+        }
+        if amount > 0 && expiration_ledger < env.ledger().sequence() {
+            panic!("Expiration ledger is in the past");
+        }
+
+        let allowance_key = (Symbol::new(&env, "allowance"), from.clone(), spender.clone());
+        env.storage().persistent().set(
+            &allowance_key,
+            &AllowanceValue {
+                amount,
+                expiration_ledger,
+            },
+        );
+
+        // Keep a live allowance from being archived before it expires
+        if amount > 0 {
+            let live_for = (expiration_ledger - env.ledger().sequence()).min(env.storage().max_ttl());
+            env.storage().persistent().extend_ttl(&allowance_key, live_for, live_for);
+        }
+
+        // ✅ Typed event
+        ApproveEvent {
+            from,
+            spender,
+            amount,
+            expiration_ledger,
+        }
+        .publish(&env);
+    }
+
+    // --- Current allowance (0 once expired) ---
+    pub fn allowance(env: Env, from: Address, spender: Address) -> i128 {
+        let allowance: Option<AllowanceValue> = env
+            .storage()
+            .persistent()
+            .get(&(Symbol::new(&env, "allowance"), from, spender));
+
+        match allowance {
+            Some(a) if a.expiration_ledger >= env.ledger().sequence() => a.amount,
+            _ => 0,
+        }
+    }
+
+    // --- Transfer on behalf of `from` (spender signs) ---
+    // The registered marketplace is pre-authorized and skips the allowance check
+    pub fn transfer_from(env: Env, spender: Address, from: Address, to: Address, amount: i128) {
+        spender.require_auth();
+
+        if amount < 0 {
+            panic_with_error!(&env, Error::InvalidAmount);
         }
+        Self::require_not_frozen(&env, &from);
+        Self::require_not_frozen(&env, &to);
 
-        from_balance -= amount;
-        to_balance += amount;
+        let marketplace: Option<Address> = env
+            .storage()
+            .instance()
+            .get(&Symbol::new(&env, "marketplace"));
+
+        if marketplace != Some(spender.clone()) {
+            Self::spend_allowance(&env, &from, &spender, amount);
+        }
 
-        env.storage().persistent().set(&from, &from_balance);
-        env.storage().persistent().set(&to, &to_balance);
+        Self::move_balance(&env, &from, &to, amount).unwrap_or_else(|e| panic_with_error!(&env, e));
 
         // ✅ Typed event
         TransferEvent { from, to, amount }.publish(&env);
     }
 
+    // --- Register the resale marketplace (owner only) ---
+    pub fn set_marketplace(env: Env, marketplace: Address) {
+        let company: CompanyInfo = env
+            .storage()
+            .instance()
+            .get(&Symbol::new(&env, "company_info"))
+            .unwrap();
+
+        company.owner.require_auth();
+
+        env.storage()
+            .instance()
+            .set(&Symbol::new(&env, "marketplace"), &marketplace);
+
+        // ✅ Typed event
+        MarketplaceSetEvent { marketplace }.publish(&env);
+    }
+
+    // --- Registered marketplace, if any ---
+    pub fn get_marketplace(env: Env) -> Option<Address> {
+        env.storage()
+            .instance()
+            .get(&Symbol::new(&env, "marketplace"))
+    }
+
     // --- Transfer with payment (for resale market) ---
     // Buyer initiates, pays seller, and receives tokens
     pub fn transfer_with_payment(
@@ -180,32 +606,161 @@ impl EquityToken {
     ) {
         // Buyer must authorize this transaction
         to.require_auth();
+        Self::require_not_frozen(&env, &from);
+        Self::require_not_frozen(&env, &to);
 
-        let mut from_balance: i128 = env.storage().persistent().get(&from).unwrap_or(0);
-        let mut to_balance: i128 = env.storage().persistent().get(&to).unwrap_or(0);
-
-        if from_balance < amount {
+        if amount < 0 {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+        if Self::balance_of(env.clone(), from.clone()) < amount {
             panic!("Seller has insufficient balance");
         }
 
+        // Resale prices must land on the configured tick
+        let price_tick = Self::get_price_tick(env.clone());
+        if price_tick > 0 && price_per_token % price_tick != 0 {
+            panic_with_error!(&env, Error::InvalidPriceTick);
+        }
+
         // Calculate payment amount
-        let payment_amount = amount * price_per_token;
+        let payment_amount = amount
+            .checked_mul(price_per_token)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::Overflow));
 
         // Transfer XLM from buyer to seller
         let xlm_client = token::Client::new(&env, &xlm_token);
         xlm_client.transfer(&to, &from, &payment_amount);
 
         // Transfer tokens from seller to buyer
-        from_balance -= amount;
-        to_balance += amount;
-
-        env.storage().persistent().set(&from, &from_balance);
-        env.storage().persistent().set(&to, &to_balance);
+        Self::move_balance(&env, &from, &to, amount).unwrap_or_else(|e| panic_with_error!(&env, e));
 
         // ✅ Emit event
         TransferEvent { from, to, amount }.publish(&env);
     }
 
+    // --- Every address currently holding tokens ---
+    // Reads one entry per holder; use `get_holders_paged` once the holder set is large
+    pub fn get_holders(env: Env) -> Vec<Address> {
+        Self::get_holders_paged(env.clone(), 0, Self::holder_count(env))
+    }
+
+    // --- Up to `limit` holders starting at position `start` ---
+    pub fn get_holders_paged(env: Env, start: u32, limit: u32) -> Vec<Address> {
+        let end = Self::holder_count(env.clone()).min(start.saturating_add(limit));
+        let mut holders = Vec::new(&env);
+        for index in start..end {
+            let holder: Address = env
+                .storage()
+                .persistent()
+                .get(&(Symbol::new(&env, "holder_at"), index))
+                .unwrap();
+            holders.push_back(holder);
+        }
+        holders
+    }
+
+    // --- Number of addresses currently holding tokens ---
+    pub fn holder_count(env: Env) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&Symbol::new(&env, "holder_count"))
+            .unwrap_or(0)
+    }
+
+    // --- Pay a dividend pro rata to current holdings (owner only) ---
+    // Only a per-token accumulator moves here; each holder's share is settled lazily when
+    // their balance next changes or they claim. Every round must use the same token.
+    pub fn distribute_dividend(env: Env, xlm_token: Address, total_amount: i128) {
+        let company: CompanyInfo = env
+            .storage()
+            .instance()
+            .get(&Symbol::new(&env, "company_info"))
+            .unwrap();
+
+        company.owner.require_auth();
+
+        if total_amount <= 0 || company.total_supply <= 0 {
+            panic!("Invalid amount");
+        }
+
+        let token_key = Symbol::new(&env, "dividend_token");
+        let dividend_token: Option<Address> = env.storage().instance().get(&token_key);
+        if dividend_token.is_some_and(|token| token != xlm_token) {
+            panic_with_error!(&env, Error::DividendTokenMismatch);
+        }
+        env.storage().instance().set(&token_key, &xlm_token);
+
+        // Fund the round up front; rounding dust stays with the contract
+        let contract_addr = env.current_contract_address();
+        token::Client::new(&env, &xlm_token).transfer(&company.owner, &contract_addr, &total_amount);
+
+        let per_token = total_amount
+            .checked_mul(DIVIDEND_SCALE)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::Overflow))
+            / company.total_supply;
+        let accumulated = Self::dividend_per_token(&env)
+            .checked_add(per_token)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::Overflow));
+        env.storage()
+            .instance()
+            .set(&Symbol::new(&env, "dividend_per_token"), &accumulated);
+
+        let round: u32 = env
+            .storage()
+            .instance()
+            .get(&Symbol::new(&env, "dividend_rounds"))
+            .unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&Symbol::new(&env, "dividend_rounds"), &(round + 1));
+
+        // ✅ Typed event
+        DividendDistributedEvent {
+            round,
+            xlm_token,
+            total_amount,
+        }
+        .publish(&env);
+    }
+
+    // --- Withdraw every unclaimed dividend (holder signs) ---
+    pub fn claim_dividend(env: Env, holder: Address) {
+        holder.require_auth();
+
+        Self::settle_dividend(&env, &holder);
+        let owed_key = (Symbol::new(&env, "dividend_owed"), holder.clone());
+        let amount: i128 = env.storage().persistent().get(&owed_key).unwrap_or(0);
+        if amount <= 0 {
+            panic_with_error!(&env, Error::NoDividend);
+        }
+
+        // Drop the balance before paying so it can never be claimed twice
+        env.storage().persistent().remove(&owed_key);
+        let dividend_token: Address = env
+            .storage()
+            .instance()
+            .get(&Symbol::new(&env, "dividend_token"))
+            .unwrap();
+        token::Client::new(&env, &dividend_token).transfer(
+            &env.current_contract_address(),
+            &holder,
+            &amount,
+        );
+
+        // ✅ Typed event
+        DividendClaimedEvent { holder, amount }.publish(&env);
+    }
+
+    // --- Unclaimed dividend owed to a holder across all rounds ---
+    pub fn get_dividend(env: Env, holder: Address) -> i128 {
+        let owed: i128 = env
+            .storage()
+            .persistent()
+            .get(&(Symbol::new(&env, "dividend_owed"), holder.clone()))
+            .unwrap_or(0);
+        owed + Self::unsettled_dividend(&env, &holder)
+    }
+
     // --- Check balance ---
     pub fn balance_of(env: Env, addr: Address) -> i128 {
         env.storage().persistent().get(&addr).unwrap_or(0)
@@ -224,6 +779,11 @@ impl EquityToken {
         } else {
             from.require_auth();
         }
+        Self::require_not_frozen(&env, &from);
+
+        if amount < 0 {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
 
         let mut balance: i128 = env.storage().persistent().get(&from).unwrap_or(0);
         if balance < amount {
@@ -233,13 +793,138 @@ impl EquityToken {
         balance -= amount;
         company.total_supply -= amount;
 
-        env.storage().persistent().set(&from, &balance);
+        Self::write_balance(&env, &from, balance);
         env.storage().instance().set(&Symbol::new(&env, "company_info"), &company);
 
         // ✅ Typed event
         BurnEvent { from, amount }.publish(&env);
     }
 
+    // --- Burn a holder's tokens (registered burner signs, e.g. on refund) ---
+    pub fn burn_from(env: Env, burner: Address, from: Address, amount: i128) -> Result<(), Error> {
+        burner.require_auth();
+
+        let registered: Option<Address> = env.storage().instance().get(&Symbol::new(&env, "burner"));
+        if registered != Some(burner) {
+            return Err(Error::NotBurner);
+        }
+        if Self::is_frozen(env.clone(), from.clone()) {
+            return Err(Error::AccountFrozen);
+        }
+
+        let mut company: CompanyInfo = env
+            .storage()
+            .instance()
+            .get(&Symbol::new(&env, "company_info"))
+            .unwrap();
+
+        let mut balance: i128 = env.storage().persistent().get(&from).unwrap_or(0);
+        if amount <= 0 || balance < amount {
+            return Err(Error::InsufficientBalance);
+        }
+
+        balance -= amount;
+        company.total_supply -= amount;
+
+        Self::write_balance(&env, &from, balance);
+        env.storage().instance().set(&Symbol::new(&env, "company_info"), &company);
+
+        // ✅ Typed event
+        BurnEvent { from, amount }.publish(&env);
+
+        Ok(())
+    }
+
+    // --- Clawback tokens to the owner (only if enabled at init) ---
+    // Holder auth is not required; holders can see `clawback_enabled` upfront
+    pub fn clawback(env: Env, from: Address, amount: i128) -> Result<(), Error> {
+        let company: CompanyInfo = env
+            .storage()
+            .instance()
+            .get(&Symbol::new(&env, "company_info"))
+            .unwrap();
+
+        company.owner.require_auth();
+
+        if !company.clawback_enabled {
+            return Err(Error::ClawbackDisabled);
+        }
+
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        Self::shift_balance(&env, &from, &company.owner, amount)?;
+
+        // ✅ Typed event
+        ClawbackEvent { from, amount }.publish(&env);
+
+        Ok(())
+    }
+
+    // --- Replace the contract code, keeping balances and settings (owner only) ---
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<(), Error> {
+        let company: CompanyInfo = env
+            .storage()
+            .instance()
+            .get(&Symbol::new(&env, "company_info"))
+            .unwrap();
+
+        company.owner.require_auth();
+
+        env.deployer().update_current_contract_wasm(new_wasm_hash.clone());
+
+        // ✅ Typed event
+        UpgradedEvent { new_wasm_hash }.publish(&env);
+
+        Ok(())
+    }
+
+    // --- Update company metadata (owner only, for rebrands) ---
+    pub fn update_metadata(
+        env: Env,
+        new_name: String,
+        new_symbol: String,
+        new_description: String,
+    ) {
+        let mut company: CompanyInfo = env
+            .storage()
+            .instance()
+            .get(&Symbol::new(&env, "company_info"))
+            .unwrap();
+
+        company.owner.require_auth();
+
+        if new_name.is_empty() || new_symbol.is_empty() || new_description.is_empty() {
+            panic!("Metadata cannot be empty");
+        }
+
+        company.name = new_name.clone();
+        company.symbol = new_symbol.clone();
+        company.description = new_description.clone();
+        env.storage().instance().set(&Symbol::new(&env, "company_info"), &company);
+
+        // ✅ Typed event
+        MetadataUpdatedEvent {
+            name: new_name,
+            symbol: new_symbol,
+            description: new_description,
+        }
+        .publish(&env);
+    }
+
+    // --- SEP-41 metadata ---
+    pub fn decimals(env: Env) -> u32 {
+        Self::get_company_info(env).decimals
+    }
+
+    pub fn name(env: Env) -> String {
+        Self::get_company_info(env).name
+    }
+
+    pub fn symbol(env: Env) -> String {
+        Self::get_company_info(env).symbol
+    }
+
     // --- Getter for tests & read-only access ---
     pub fn get_company_info(env: Env) -> CompanyInfo {
         env.storage()
@@ -249,5 +934,178 @@ impl EquityToken {
     }
 }
 
+impl EquityToken {
+    // --- Move tokens between two balances; total_supply is untouched ---
+    // Counts against the sender's transfer limit, whichever entrypoint moves the tokens
+    fn move_balance(env: &Env, from: &Address, to: &Address, amount: i128) -> Result<(), Error> {
+        if amount < 0 {
+            return Err(Error::InvalidAmount);
+        }
+        Self::track_transfer_limit(env, from, amount);
+        Self::shift_balance(env, from, to, amount)
+    }
+
+    // --- Move tokens without touching transfer limits (clawback is not the holder's transfer) ---
+    // The debit is written before the credit is read, so moving to yourself nets to zero
+    fn shift_balance(env: &Env, from: &Address, to: &Address, amount: i128) -> Result<(), Error> {
+        if amount < 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let from_balance = Self::balance_of(env.clone(), from.clone());
+        if from_balance < amount {
+            return Err(Error::InsufficientBalance);
+        }
+        Self::write_balance(env, from, from_balance - amount);
+
+        let to_balance = Self::balance_of(env.clone(), to.clone())
+            .checked_add(amount)
+            .ok_or(Error::Overflow)?;
+        Self::write_balance(env, to, to_balance);
+
+        Ok(())
+    }
+
+    // --- Store a balance and keep the holder set in sync ---
+    fn write_balance(env: &Env, addr: &Address, balance: i128) {
+        // Dividends accrued on the old balance are fixed before it changes
+        Self::settle_dividend(env, addr);
+        env.storage().persistent().set(addr, &balance);
+
+        // Holders live in indexed slots so joining or leaving touches a fixed number of entries
+        let index_key = (Symbol::new(env, "holder_index"), addr.clone());
+        let index: Option<u32> = env.storage().persistent().get(&index_key);
+        let count = Self::holder_count(env.clone());
+        match index {
+            None if balance > 0 => {
+                env.storage().persistent().set(&index_key, &count);
+                env.storage()
+                    .persistent()
+                    .set(&(Symbol::new(env, "holder_at"), count), addr);
+                env.storage()
+                    .persistent()
+                    .set(&Symbol::new(env, "holder_count"), &(count + 1));
+            }
+            Some(index) if balance <= 0 => {
+                // Swap the last holder into the freed slot
+                let last = count - 1;
+                let last_key = (Symbol::new(env, "holder_at"), last);
+                if index != last {
+                    let moved: Address = env.storage().persistent().get(&last_key).unwrap();
+                    env.storage()
+                        .persistent()
+                        .set(&(Symbol::new(env, "holder_at"), index), &moved);
+                    env.storage()
+                        .persistent()
+                        .set(&(Symbol::new(env, "holder_index"), moved), &index);
+                }
+                env.storage().persistent().remove(&last_key);
+                env.storage().persistent().remove(&index_key);
+                env.storage()
+                    .persistent()
+                    .set(&Symbol::new(env, "holder_count"), &last);
+            }
+            _ => {}
+        }
+    }
+
+    fn dividend_per_token(env: &Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&Symbol::new(env, "dividend_per_token"))
+            .unwrap_or(0)
+    }
+
+    // --- Dividend accrued on the current balance since the holder was last settled ---
+    fn unsettled_dividend(env: &Env, holder: &Address) -> i128 {
+        let settled: i128 = env
+            .storage()
+            .persistent()
+            .get(&(Symbol::new(env, "dividend_settled"), holder.clone()))
+            .unwrap_or(0);
+        Self::balance_of(env.clone(), holder.clone())
+            .checked_mul(Self::dividend_per_token(env) - settled)
+            .unwrap_or_else(|| panic_with_error!(env, Error::Overflow))
+            / DIVIDEND_SCALE
+    }
+
+    // --- Move accrued dividend into the holder's owed balance ---
+    fn settle_dividend(env: &Env, holder: &Address) {
+        let accumulated = Self::dividend_per_token(env);
+        let settled_key = (Symbol::new(env, "dividend_settled"), holder.clone());
+        let settled: i128 = env.storage().persistent().get(&settled_key).unwrap_or(0);
+        if settled == accumulated {
+            return;
+        }
+
+        let accrued = Self::unsettled_dividend(env, holder);
+        if accrued > 0 {
+            let owed_key = (Symbol::new(env, "dividend_owed"), holder.clone());
+            let owed: i128 = env.storage().persistent().get(&owed_key).unwrap_or(0);
+            env.storage().persistent().set(&owed_key, &(owed + accrued));
+        }
+        env.storage().persistent().set(&settled_key, &accumulated);
+    }
+
+    // --- Reject moves touching a frozen account ---
+    fn require_not_frozen(env: &Env, addr: &Address) {
+        if Self::is_frozen(env.clone(), addr.clone()) {
+            panic_with_error!(env, Error::AccountFrozen);
+        }
+    }
+
+    // --- Deduct `amount` from a live allowance (SEP-41) ---
+    fn spend_allowance(env: &Env, from: &Address, spender: &Address, amount: i128) {
+        let allowance_key = (Symbol::new(env, "allowance"), from.clone(), spender.clone());
+        let mut allowance: AllowanceValue = env
+            .storage()
+            .persistent()
+            .get(&allowance_key)
+            .unwrap_or_else(|| panic!("Insufficient allowance"));
+
+        if allowance.expiration_ledger < env.ledger().sequence() || allowance.amount < amount {
+            panic!("Insufficient allowance");
+        }
+
+        allowance.amount -= amount;
+        env.storage().persistent().set(&allowance_key, &allowance);
+    }
+
+    // --- Count `amount` against the sender's daily transfer limit ---
+    // The owner, the registered minter and exempted escrows distribute supply and are exempt
+    fn track_transfer_limit(env: &Env, from: &Address, amount: i128) {
+        let limit = Self::get_transfer_limit(env.clone());
+        if limit == 0 {
+            return;
+        }
+
+        let company = Self::get_company_info(env.clone());
+        if *from == company.owner
+            || Self::get_minter(env.clone()).as_ref() == Some(from)
+            || Self::is_limit_exempt(env.clone(), from.clone())
+        {
+            return;
+        }
+
+        let key = (Symbol::new(env, "transferred"), from.clone());
+        let now = env.ledger().timestamp();
+        let (window_start, transferred): (u64, i128) = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .filter(|(start, _): &(u64, i128)| now < start.saturating_add(TRANSFER_WINDOW))
+            .unwrap_or((now, 0));
+
+        let total = transferred
+            .checked_add(amount)
+            .unwrap_or_else(|| panic_with_error!(env, Error::Overflow));
+        if total > limit {
+            panic_with_error!(env, Error::TransferLimitExceeded);
+        }
+
+        env.storage().persistent().set(&key, &(window_start, total));
+    }
+}
+
 #[cfg(test)]
 mod test;
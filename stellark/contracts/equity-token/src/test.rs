@@ -0,0 +1,664 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{
+    testutils::{storage::Persistent as _, Address as _, Ledger, MockAuth, MockAuthInvoke},
+    xdr, Bytes, IntoVal,
+};
+
+fn setup(env: &Env) -> (EquityTokenClient<'_>, Address) {
+    let contract_id = env.register(EquityToken, ());
+    let client = EquityTokenClient::new(env, &contract_id);
+    let owner = Address::generate(env);
+
+    client.init_company(
+        &String::from_str(env, "Stellark Labs"),
+        &String::from_str(env, "STLK"),
+        &1_000_000,
+        &owner,
+        &20,
+        &String::from_str(env, "Equity in Stellark Labs"),
+        &10,
+        &5_000_000,
+        &true,
+        &7,
+    );
+
+    (client, owner)
+}
+
+/// Every balance summed must equal total_supply
+fn assert_supply_invariant(client: &EquityTokenClient) {
+    let held: i128 = client.get_holders().iter().map(|h| client.balance_of(&h)).sum();
+    assert_eq!(held, client.get_company_info().total_supply);
+}
+
+// Smallest wasm the host accepts: an empty module carrying only the env meta section
+const EMPTY_WASM: &[u8] =
+    b"\0asm\x01\0\0\0\0\x1e\x11contractenvmetav0\0\0\0\0\0\0\0\x17\0\0\0\0";
+
+/// Hash of the wasm a contract instance currently runs
+fn wasm_hash_of(env: &Env, contract: &Address) -> [u8; 32] {
+    let contract = xdr::ScAddress::from(contract);
+    env.to_snapshot()
+        .ledger
+        .ledger_entries
+        .iter()
+        .find_map(|(_, (entry, _))| match &entry.data {
+            xdr::LedgerEntryData::ContractData(data)
+                if data.contract == contract && data.key == xdr::ScVal::LedgerKeyContractInstance =>
+            {
+                match &data.val {
+                    xdr::ScVal::ContractInstance(xdr::ScContractInstance {
+                        executable: xdr::ContractExecutable::Wasm(hash),
+                        ..
+                    }) => Some(hash.0),
+                    _ => None,
+                }
+            }
+            _ => None,
+        })
+        .unwrap()
+}
+
+#[test]
+fn test_update_metadata_by_owner() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _owner) = setup(&env);
+
+    client.update_metadata(
+        &String::from_str(&env, "Stellark Inc"),
+        &String::from_str(&env, "STLKI"),
+        &String::from_str(&env, "Rebranded"),
+    );
+
+    let info = client.get_company_info();
+    assert_eq!(info.name, String::from_str(&env, "Stellark Inc"));
+    assert_eq!(info.symbol, String::from_str(&env, "STLKI"));
+    assert_eq!(info.description, String::from_str(&env, "Rebranded"));
+}
+
+#[test]
+fn test_update_metadata_rejects_non_owner() {
+    let env = Env::default();
+    let (client, _owner) = setup(&env);
+    let stranger = Address::generate(&env);
+
+    let name = String::from_str(&env, "Hijacked");
+    let symbol = String::from_str(&env, "HJK");
+    let description = String::from_str(&env, "Not the owner");
+
+    let result = client
+        .mock_auths(&[MockAuth {
+            address: &stranger,
+            invoke: &MockAuthInvoke {
+                contract: &client.address,
+                fn_name: "update_metadata",
+                args: (name.clone(), symbol.clone(), description.clone()).into_val(&env),
+                sub_invokes: &[],
+            },
+        }])
+        .try_update_metadata(&name, &symbol, &description);
+    assert!(result.is_err());
+
+    assert_eq!(client.get_company_info().name, String::from_str(&env, "Stellark Labs"));
+}
+
+#[test]
+fn test_update_metadata_rejects_empty_strings() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _owner) = setup(&env);
+
+    let result = client.try_update_metadata(
+        &String::from_str(&env, ""),
+        &String::from_str(&env, "STLK"),
+        &String::from_str(&env, "Empty name"),
+    );
+    assert!(result.is_err());
+}
+
+fn try_init(
+    env: &Env,
+    total_supply: i128,
+    equity_percent: i128,
+    token_price: i128,
+    target_amount: i128,
+) -> Result<(), Error> {
+    let contract_id = env.register(EquityToken, ());
+    let client = EquityTokenClient::new(env, &contract_id);
+
+    match client.try_init_company(
+        &String::from_str(env, "Stellark Labs"),
+        &String::from_str(env, "STLK"),
+        &total_supply,
+        &Address::generate(env),
+        &equity_percent,
+        &String::from_str(env, "Equity in Stellark Labs"),
+        &token_price,
+        &target_amount,
+        &false,
+        &7,
+    ) {
+        Ok(_) => Ok(()),
+        Err(Ok(err)) => Err(err),
+        Err(Err(_)) => panic!("unexpected invoke error"),
+    }
+}
+
+#[test]
+fn test_init_company_validates_tokenomics() {
+    let env = Env::default();
+
+    assert_eq!(try_init(&env, 1_000, 20, 10, 10_000), Ok(()));
+    assert_eq!(try_init(&env, 0, 20, 10, 10_000), Err(Error::InvalidTotalSupply));
+    assert_eq!(try_init(&env, 1_000, 0, 10, 10_000), Err(Error::InvalidEquityPercent));
+    assert_eq!(try_init(&env, 1_000, 101, 10, 10_000), Err(Error::InvalidEquityPercent));
+    assert_eq!(try_init(&env, 1_000, 20, 0, 10_000), Err(Error::InvalidTokenPrice));
+    assert_eq!(try_init(&env, 1_000, 20, 10, 10_001), Err(Error::InvalidTargetAmount));
+    assert_eq!(try_init(&env, 1_000, 20, 10, 0), Err(Error::InvalidTargetAmount));
+}
+
+#[test]
+fn test_sep41_metadata() {
+    let env = Env::default();
+    let (client, _owner) = setup(&env);
+
+    assert_eq!(client.decimals(), 7);
+    assert_eq!(client.name(), String::from_str(&env, "Stellark Labs"));
+    assert_eq!(client.symbol(), String::from_str(&env, "STLK"));
+}
+
+#[test]
+fn test_init_company_rejects_excess_decimals() {
+    let env = Env::default();
+    let contract_id = env.register(EquityToken, ());
+    let client = EquityTokenClient::new(&env, &contract_id);
+
+    let result = client.try_init_company(
+        &String::from_str(&env, "Stellark Labs"),
+        &String::from_str(&env, "STLK"),
+        &1_000,
+        &Address::generate(&env),
+        &20,
+        &String::from_str(&env, "Equity in Stellark Labs"),
+        &10,
+        &1_000,
+        &false,
+        &19,
+    );
+    assert_eq!(result, Err(Ok(Error::InvalidDecimals)));
+}
+
+#[test]
+fn test_init_company_only_once() {
+    let env = Env::default();
+    let (client, owner) = setup(&env);
+
+    let result = client.try_init_company(
+        &String::from_str(&env, "Again"),
+        &String::from_str(&env, "AGN"),
+        &1_000,
+        &owner,
+        &20,
+        &String::from_str(&env, "Second init"),
+        &10,
+        &1_000,
+        &false,
+        &7,
+    );
+    assert_eq!(result, Err(Ok(Error::AlreadyInitialized)));
+}
+
+#[test]
+fn test_marketplace_skips_allowance() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, owner) = setup(&env);
+    let holder = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let marketplace = Address::generate(&env);
+
+    client.transfer(&owner, &holder, &1_000);
+    client.set_marketplace(&marketplace);
+    assert_eq!(client.get_marketplace(), Some(marketplace.clone()));
+
+    client.transfer_from(&marketplace, &holder, &buyer, &400);
+    assert_eq!(client.balance_of(&holder), 600);
+    assert_eq!(client.balance_of(&buyer), 400);
+}
+
+#[test]
+fn test_transfer_from_requires_allowance() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, owner) = setup(&env);
+    let holder = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let spender = Address::generate(&env);
+
+    client.transfer(&owner, &holder, &1_000);
+    client.set_marketplace(&Address::generate(&env));
+
+    assert!(client.try_transfer_from(&spender, &holder, &buyer, &400).is_err());
+
+    client.approve(&holder, &spender, &500, &100);
+    assert_eq!(client.allowance(&holder, &spender), 500);
+
+    client.transfer_from(&spender, &holder, &buyer, &400);
+    assert_eq!(client.allowance(&holder, &spender), 100);
+    assert_eq!(client.balance_of(&buyer), 400);
+
+    assert!(client.try_transfer_from(&spender, &holder, &buyer, &200).is_err());
+}
+
+#[test]
+fn test_clawback_when_enabled() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, owner) = setup(&env);
+    let holder = Address::generate(&env);
+
+    client.transfer(&owner, &holder, &1_000);
+    client.clawback(&holder, &400);
+
+    assert_eq!(client.balance_of(&holder), 600);
+    assert_eq!(client.balance_of(&owner), 999_400);
+    assert_supply_invariant(&client);
+
+    assert_eq!(client.try_clawback(&holder, &0), Err(Ok(Error::InvalidAmount)));
+}
+
+#[test]
+fn test_clawback_rejected_when_disabled() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(EquityToken, ());
+    let client = EquityTokenClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let holder = Address::generate(&env);
+
+    client.init_company(
+        &String::from_str(&env, "Stellark Labs"),
+        &String::from_str(&env, "STLK"),
+        &1_000_000,
+        &owner,
+        &20,
+        &String::from_str(&env, "Equity in Stellark Labs"),
+        &10,
+        &5_000_000,
+        &false,
+        &7,
+    );
+    client.transfer(&owner, &holder, &1_000);
+
+    assert_eq!(client.try_clawback(&holder, &400), Err(Ok(Error::ClawbackDisabled)));
+    assert_eq!(client.balance_of(&holder), 1_000);
+}
+
+#[test]
+fn test_registered_minter_can_issue() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, owner) = setup(&env);
+    let minter = Address::generate(&env);
+    let investor = Address::generate(&env);
+
+    client.set_minter(&minter);
+    assert_eq!(client.get_minter(), Some(minter.clone()));
+
+    client.mint_to(&minter, &investor, &250);
+    assert_eq!(client.balance_of(&investor), 250);
+    assert_eq!(client.balance_of(&owner), 999_750);
+
+    assert_eq!(client.try_mint_to(&minter, &investor, &0), Err(Ok(Error::InvalidAmount)));
+}
+
+#[test]
+fn test_non_minter_cannot_issue() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _owner) = setup(&env);
+    let investor = Address::generate(&env);
+
+    client.set_minter(&Address::generate(&env));
+
+    let stranger = Address::generate(&env);
+    assert_eq!(client.try_mint_to(&stranger, &investor, &250), Err(Ok(Error::NotMinter)));
+    assert_eq!(client.balance_of(&investor), 0);
+}
+
+#[test]
+fn test_transfer_limit_per_day() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, owner) = setup(&env);
+    let holder = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    client.transfer(&owner, &holder, &1_000);
+    client.set_transfer_limit(&300);
+    assert_eq!(client.get_transfer_limit(), 300);
+
+    client.transfer(&holder, &buyer, &200);
+    client.transfer(&holder, &buyer, &100);
+    assert_eq!(
+        client.try_transfer(&holder, &buyer, &1),
+        Err(Ok(soroban_sdk::Error::from(Error::TransferLimitExceeded)))
+    );
+
+    // The owner distributing supply is not limited
+    client.transfer(&owner, &holder, &500);
+
+    env.ledger().with_mut(|l| l.timestamp += 86_400);
+    client.transfer(&holder, &buyer, &300);
+    assert_eq!(client.balance_of(&buyer), 600);
+}
+
+#[test]
+fn test_transfer_limit_applies_to_transfer_from() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, owner) = setup(&env);
+    let holder = Address::generate(&env);
+    let second_wallet = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    client.transfer(&owner, &holder, &1_000);
+    client.set_transfer_limit(&300);
+
+    // Routing through an approved address shares the holder's daily cap
+    client.transfer(&holder, &buyer, &200);
+    client.approve(&holder, &second_wallet, &1_000, &1_000);
+    client.transfer_from(&second_wallet, &holder, &buyer, &100);
+    assert_eq!(
+        client.try_transfer_from(&second_wallet, &holder, &buyer, &1),
+        Err(Ok(soroban_sdk::Error::from(Error::TransferLimitExceeded)))
+    );
+    assert_eq!(client.balance_of(&buyer), 300);
+}
+
+#[test]
+fn test_limit_exempt_address_skips_transfer_limit() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, owner) = setup(&env);
+    let escrow = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    client.transfer(&owner, &escrow, &1_000);
+    client.set_transfer_limit(&300);
+    client.set_limit_exempt(&escrow, &true);
+    assert!(client.is_limit_exempt(&escrow));
+
+    client.transfer(&escrow, &buyer, &500);
+
+    client.set_limit_exempt(&escrow, &false);
+    assert_eq!(
+        client.try_transfer(&escrow, &buyer, &301),
+        Err(Ok(soroban_sdk::Error::from(Error::TransferLimitExceeded)))
+    );
+}
+
+#[test]
+fn test_frozen_account_cannot_move_tokens() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, owner) = setup(&env);
+    let holder = Address::generate(&env);
+    let other = Address::generate(&env);
+    client.transfer(&owner, &holder, &1_000);
+
+    client.set_frozen(&holder, &true);
+    assert!(client.is_frozen(&holder));
+    let frozen = Err(Ok(soroban_sdk::Error::from(Error::AccountFrozen)));
+    assert_eq!(client.try_transfer(&holder, &other, &100), frozen);
+    assert_eq!(client.try_transfer(&owner, &holder, &100), frozen);
+    assert_eq!(client.try_burn(&holder, &100), frozen);
+
+    // Still queryable, and clawback still reaches it
+    assert_eq!(client.balance_of(&holder), 1_000);
+    client.clawback(&holder, &400);
+    assert_eq!(client.balance_of(&holder), 600);
+
+    client.set_frozen(&holder, &false);
+    client.transfer(&holder, &other, &100);
+    assert_eq!(client.balance_of(&other), 100);
+}
+
+#[test]
+fn test_registered_burner_reduces_supply() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, owner) = setup(&env);
+    let burner = Address::generate(&env);
+    let holder = Address::generate(&env);
+
+    client.transfer(&owner, &holder, &1_000);
+    assert_eq!(client.try_burn_from(&burner, &holder, &400), Err(Ok(Error::NotBurner)));
+
+    client.set_burner(&burner);
+    assert_eq!(client.get_burner(), Some(burner.clone()));
+
+    client.burn_from(&burner, &holder, &400);
+    assert_eq!(client.balance_of(&holder), 600);
+    assert_eq!(client.get_company_info().total_supply, 999_600);
+    assert_supply_invariant(&client);
+}
+
+#[test]
+fn test_moves_never_change_supply() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, owner) = setup(&env);
+    let holder = Address::generate(&env);
+    let minter = Address::generate(&env);
+    let invalid = Err(Ok(soroban_sdk::Error::from(Error::InvalidAmount)));
+
+    client.transfer(&owner, &holder, &1_000);
+    assert_supply_invariant(&client);
+
+    // Moving to yourself nets to zero instead of double-crediting
+    client.transfer(&holder, &holder, &400);
+    assert_eq!(client.balance_of(&holder), 1_000);
+    client.set_minter(&minter);
+    client.mint_to(&minter, &owner, &500);
+    assert_eq!(client.balance_of(&owner), 999_000);
+    client.clawback(&owner, &500);
+    assert_eq!(client.balance_of(&owner), 999_000);
+    assert_supply_invariant(&client);
+
+    // Negative amounts would credit the sender
+    assert_eq!(client.try_transfer(&holder, &owner, &-100), invalid);
+    assert_eq!(client.try_burn(&holder, &-100), invalid);
+    assert_eq!(client.balance_of(&holder), 1_000);
+
+    client.burn(&holder, &250);
+    assert_eq!(client.get_company_info().total_supply, 999_750);
+    assert_supply_invariant(&client);
+}
+
+#[test]
+fn test_mint_payment_overflow_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(EquityToken, ());
+    let client = EquityTokenClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let xlm = env.register_stellar_asset_contract_v2(owner.clone()).address();
+
+    client.init_company(
+        &String::from_str(&env, "Stellark Labs"),
+        &String::from_str(&env, "STLK"),
+        &1_000,
+        &owner,
+        &20,
+        &String::from_str(&env, "Equity in Stellark Labs"),
+        &(i128::MAX / 2),
+        &1_000,
+        &false,
+        &7,
+    );
+
+    assert_eq!(
+        client.try_mint(&buyer, &3, &xlm),
+        Err(Ok(soroban_sdk::Error::from(Error::Overflow)))
+    );
+    assert_eq!(
+        client.try_transfer_with_payment(&owner, &buyer, &3, &i128::MAX, &xlm),
+        Err(Ok(soroban_sdk::Error::from(Error::Overflow)))
+    );
+    assert_eq!(client.balance_of(&buyer), 0);
+}
+
+#[test]
+fn test_allowance_lives_until_expiration() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, owner) = setup(&env);
+    let holder = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    client.transfer(&owner, &holder, &1_000);
+    client.set_marketplace(&Address::generate(&env));
+
+    let expiration = env.ledger().sequence() + 500_000;
+    client.approve(&holder, &spender, &500, &expiration);
+
+    // The allowance entry is kept alive at least until it expires
+    let ttl = env.as_contract(&client.address, || {
+        env.storage()
+            .persistent()
+            .get_ttl(&(Symbol::new(&env, "allowance"), holder.clone(), spender.clone()))
+    });
+    assert!(ttl >= 500_000);
+
+    client.transfer_from(&spender, &holder, &buyer, &200);
+    assert_eq!(client.allowance(&holder, &spender), 300);
+
+    env.ledger().with_mut(|l| l.sequence_number = expiration + 1);
+    assert_eq!(client.allowance(&holder, &spender), 0);
+    assert!(client.try_transfer_from(&spender, &holder, &buyer, &100).is_err());
+    assert!(client.try_transfer_from(&spender, &holder, &buyer, &-1).is_err());
+}
+
+#[test]
+fn test_resale_price_must_match_tick() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, owner) = setup(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let xlm = env.register_stellar_asset_contract_v2(owner.clone()).address();
+    soroban_sdk::token::StellarAssetClient::new(&env, &xlm).mint(&buyer, &10_000);
+
+    client.transfer(&owner, &seller, &100);
+    client.set_price_tick(&5);
+    assert_eq!(client.get_price_tick(), 5);
+
+    assert_eq!(
+        client.try_transfer_with_payment(&seller, &buyer, &10, &12, &xlm),
+        Err(Ok(soroban_sdk::Error::from(Error::InvalidPriceTick)))
+    );
+
+    client.transfer_with_payment(&seller, &buyer, &10, &15, &xlm);
+    assert_eq!(client.balance_of(&buyer), 10);
+    assert_eq!(soroban_sdk::token::TokenClient::new(&env, &xlm).balance(&seller), 150);
+}
+
+#[test]
+fn test_holder_set_tracks_balances() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, owner) = setup(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+
+    assert_eq!(client.get_holders(), soroban_sdk::vec![&env, owner.clone()]);
+
+    client.transfer(&owner, &alice, &100);
+    client.transfer(&owner, &bob, &50);
+    assert_eq!(client.holder_count(), 3);
+
+    // Holders drop out once their balance reaches zero, whether by transfer or burn
+    client.transfer(&alice, &bob, &100);
+    client.burn(&bob, &150);
+    assert_eq!(client.get_holders(), soroban_sdk::vec![&env, owner.clone()]);
+
+    client.transfer(&owner, &alice, &1);
+    assert_eq!(client.get_holders(), soroban_sdk::vec![&env, owner.clone(), alice.clone()]);
+
+    // Leaving moves the last holder into the freed slot
+    client.transfer(&owner, &bob, &1);
+    client.transfer(&alice, &owner, &1);
+    assert_eq!(client.get_holders(), soroban_sdk::vec![&env, owner.clone(), bob.clone()]);
+    assert_eq!(client.get_holders_paged(&1, &5), soroban_sdk::vec![&env, bob]);
+    assert_eq!(client.get_holders_paged(&2, &5).len(), 0);
+}
+
+#[test]
+fn test_upgrade_replaces_contract_code() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, owner) = setup(&env);
+    let new_wasm_hash = env.deployer().upload_contract_wasm(Bytes::from_slice(&env, EMPTY_WASM));
+    assert_ne!(wasm_hash_of(&env, &client.address), new_wasm_hash.to_array());
+
+    client.upgrade(&new_wasm_hash);
+    assert_eq!(env.auths()[0].0, owner);
+    assert_eq!(wasm_hash_of(&env, &client.address), new_wasm_hash.to_array());
+}
+
+#[test]
+fn test_dividend_paid_pro_rata_per_round() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, owner) = setup(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let xlm = env.register_stellar_asset_contract_v2(owner.clone()).address();
+    soroban_sdk::token::StellarAssetClient::new(&env, &xlm).mint(&owner, &11_000);
+    let xlm_client = soroban_sdk::token::TokenClient::new(&env, &xlm);
+
+    client.transfer(&owner, &alice, &250_000);
+    client.transfer(&owner, &bob, &100_000);
+    client.distribute_dividend(&xlm, &10_000);
+    assert_eq!(client.get_dividend(&alice), 2_500);
+    assert_eq!(client.get_dividend(&owner), 6_500);
+
+    client.claim_dividend(&alice);
+    assert_eq!(xlm_client.balance(&alice), 2_500);
+    assert_eq!(
+        client.try_claim_dividend(&alice),
+        Err(Ok(soroban_sdk::Error::from(Error::NoDividend)))
+    );
+
+    // Later rounds follow balances at their own time; earlier accruals are kept
+    client.transfer(&alice, &bob, &250_000);
+    client.distribute_dividend(&xlm, &1_000);
+    assert_eq!(client.get_dividend(&alice), 0);
+    assert_eq!(client.get_dividend(&bob), 1_000 + 350);
+
+    client.claim_dividend(&bob);
+    assert_eq!(xlm_client.balance(&bob), 1_000 + 350);
+}
+
+#[test]
+fn test_dividend_rounds_share_one_token() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, owner) = setup(&env);
+    let xlm = env.register_stellar_asset_contract_v2(owner.clone()).address();
+    let other = env.register_stellar_asset_contract_v2(owner.clone()).address();
+    soroban_sdk::token::StellarAssetClient::new(&env, &xlm).mint(&owner, &1_000);
+    soroban_sdk::token::StellarAssetClient::new(&env, &other).mint(&owner, &1_000);
+
+    client.distribute_dividend(&xlm, &1_000);
+    assert_eq!(
+        client.try_distribute_dividend(&other, &1_000),
+        Err(Ok(soroban_sdk::Error::from(Error::DividendTokenMismatch)))
+    );
+}
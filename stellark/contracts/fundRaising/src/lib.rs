@@ -1,6 +1,7 @@
 #![no_std]
 use soroban_sdk::{
-    contract, contractimpl, contracttype, contracterror, contractevent, token, Address, Env, Vec
+    contract, contractclient, contractimpl, contracttype, contracterror, contractevent,
+    panic_with_error, symbol_short, token, xdr::ToXdr, Address, BytesN, Env, IntoVal, String, Symbol, Val, Vec
 };
 
 #[contract]
@@ -21,6 +22,32 @@ pub struct Campaign {
     pub deadline: u64,                // Unix timestamp deadline
     pub min_investment: i128,         // Minimum investment amount
     pub max_investment: i128,         // Maximum investment per investor (0 = no limit)
+    pub created_at: u64,              // Unix timestamp the campaign was created
+    pub withdrawn_amount: i128,       // Amount released from escrow (withdrawals and sweeps)
+    pub published: bool,              // Visible to discovery and open to investment
+    pub refunded_amount: i128,        // Total refunded to investors
+    pub refund_deadline: u64,         // Refunds guaranteed until this timestamp (0 = not set)
+    pub max_investments_per_investor: u32, // Separate invest calls allowed per investor (0 = no limit)
+    pub notify_contract: Option<Address>, // Receives `on_target_reached` when the target is crossed
+    pub activated_at: u64,            // When the company activated the campaign (0 = never)
+    pub tags: Vec<Symbol>,            // Searchable tags, e.g. "green", "AI", "seed"
+    pub closed_at: u64,               // When close_campaign last closed it (0 = never)
+    pub redeemable: bool,             // Investors may burn equity tokens to redeem escrowed XLM
+    pub redeemed_amount: i128,        // Total XLM paid out through redemptions
+    pub max_tokens_per_investor: i128, // Equity tokens one investor may receive (0 = no limit)
+    pub min_investors: u32,           // Investors needed before investments bind (0 = binding at once)
+    pub locked: bool,                 // Whether min_investors has been reached
+    pub fee_bps_override: Option<u32>, // Negotiated platform fee; replaces the global fee when set
+    pub soft_cap: i128,               // Minimum raise for the campaign to succeed at the deadline
+    pub private_deadline: u64,        // Whitelist-only investing until this time (0 = public from the start)
+    pub auto_withdraw: bool,          // Release funds and close as soon as the target is reached
+    pub payment_token: Option<Address>, // Token the raise is denominated in (None = platform XLM token)
+    pub original_deadline: u64,       // Deadline at creation; bounds how far extend_deadline may go
+    pub max_investors: u32,           // Distinct investors allowed (0 = no limit)
+    pub requires_whitelist: bool,     // Only whitelisted (KYC'd) investors may invest at any time
+    pub auto_convert_residual: bool,  // At close, issue investors equity for the escrow still held
+    pub parent_campaign_id: Option<u64>, // Earlier round this one follows on from, e.g. seed -> Series A
+    pub allow_oversubscription: bool, // Accept investments past the target (false = hard cap at target)
 }
 
 // -----------------------------
@@ -32,7 +59,70 @@ pub struct Investment {
     pub investor: Address,
     pub amount_invested: i128,        // Total XLM invested
     pub tokens_received: i128,        // Total equity tokens received
+    pub tokens_claimed: i128,         // Equity tokens already transferred to the investor
     pub timestamp: u64,               // When investment was made
+    pub refunded: bool,               // Whether the investment was refunded
+}
+
+// -----------------------------
+// ⏸️ Pending Commitment
+// -----------------------------
+#[derive(Clone)]
+#[contracttype]
+pub struct Commitment {
+    pub amount: i128,                 // XLM escrowed, not yet exchanged for tokens
+    pub committed_at: u64,            // Must be confirmed within COMMIT_WINDOW of this
+}
+
+// -----------------------------
+// 🪜 Price Tier
+// -----------------------------
+#[derive(Clone)]
+#[contracttype]
+pub struct PriceTier {
+    pub up_to: i128,                  // Tier applies while raised_amount is below this
+    pub price_per_token: i128,        // Price per equity token in stroops within the tier
+}
+
+// -----------------------------
+// 📉 Price Decay (Dutch auction)
+// -----------------------------
+#[derive(Clone)]
+#[contracttype]
+pub struct PriceDecay {
+    pub start_price: i128,            // Price per token at campaign creation
+    pub floor_price: i128,            // Price per token reached at the deadline
+}
+
+// -----------------------------
+// 🐋 Progressive Investor Cap
+// -----------------------------
+#[derive(Clone)]
+#[contracttype]
+pub struct ProgressiveCap {
+    pub start_cap: i128,              // Per-investor cap while nothing is raised
+    pub end_cap: i128,                // Per-investor cap once the target is reached
+}
+
+// -----------------------------
+// ⏳ Vesting Schedule
+// -----------------------------
+#[derive(Clone)]
+#[contracttype]
+pub struct VestingSchedule {
+    pub cliff: u64,                   // Seconds after `start` before anything vests
+    pub duration: u64,                // Seconds after `start` until everything has vested
+    pub start: u64,                   // Unix timestamp vesting starts from
+}
+
+// -----------------------------
+// 🎁 Bonus Token Allocation
+// -----------------------------
+#[derive(Clone)]
+#[contracttype]
+pub struct BonusToken {
+    pub token_addr: Address,          // Secondary reward token (pre-funded to this contract)
+    pub ratio_bps: i128,              // Bonus tokens per equity token, in bps
 }
 
 // -----------------------------
@@ -46,6 +136,40 @@ pub struct CampaignStats {
     pub total_raised: i128,
 }
 
+#[derive(Clone)]
+#[contracttype]
+pub struct CampaignDetailStats {
+    pub investor_count: u32,
+    pub raised_amount: i128,
+    pub avg_investment: i128,         // Raised amount per investor
+    pub largest_investment: i128,     // Largest single invest call
+}
+
+// -----------------------------
+// 🏢 Company Dashboard
+// -----------------------------
+#[derive(Clone)]
+#[contracttype]
+pub struct CompanyDashboard {
+    pub total_campaigns: u32,
+    pub active: u32,
+    pub succeeded: u32,               // Campaigns that reached their target
+    pub total_raised: i128,
+    pub total_investors: u32,         // Sum of per-campaign investor counts
+}
+
+// -----------------------------
+// 🧾 Event Log Entry
+// -----------------------------
+#[derive(Clone)]
+#[contracttype]
+pub struct EventRecord {
+    pub kind: Symbol,                 // "created", "activated", "invested", "withdrawn" or "closed"
+    pub campaign_id: u64,
+    pub amount: i128,                 // Amount relevant to the event (0 if none)
+    pub timestamp: u64,
+}
+
 // -----------------------------
 // ❌ Error Codes
 // -----------------------------
@@ -67,6 +191,30 @@ pub enum Error {
     CannotWithdraw = 12,
     TransferFailed = 13,
     InsufficientBalance = 14,
+    CampaignNotPublished = 15,
+    CampaignAlreadyPublished = 16,
+    RefundUnavailable = 17,
+    RefundWindowClosed = 18,
+    RefundWindowOpen = 19,
+    TooManyInvestments = 20,
+    EquityCapExceeded = 21,
+    CampaignAlreadyActivated = 22,
+    ReactivationUnavailable = 23,
+    ReactivationWindowPassed = 24,
+    RedemptionDisabled = 25,
+    InvestmentLocked = 26,
+    CommitmentNotFound = 27,
+    CommitmentExists = 28,
+    CommitmentExpired = 29,
+    NothingToClaim = 30,
+    NotWhitelisted = 31,
+    Overflow = 32,
+    Paused = 33,
+    RaiseTokenNotAllowed = 34,
+    RefundsFrozen = 35,
+    InvestorLimitReached = 36,
+    InvalidParentCampaign = 37,
+    Reentrancy = 38,
 }
 
 // -----------------------------
@@ -77,9 +225,32 @@ pub struct InitEvent {
     pub admin: Address,
 }
 
+#[contractevent]
+pub struct PausedEvent {
+    pub paused: bool,
+}
+
+#[contractevent]
+pub struct RefundsFrozenEvent {
+    pub frozen: bool,
+}
+
+#[contractevent]
+pub struct UpgradedEvent {
+    pub new_wasm_hash: BytesN<32>,
+}
+
+#[contractevent]
+pub struct AdminTransferredEvent {
+    pub old_admin: Address,
+    pub new_admin: Address,
+}
+
 #[contractevent]
 pub struct CampaignCreatedEvent {
+    #[topic]
     pub campaign_id: u64,
+    #[topic]
     pub company: Address,
     pub target_amount: i128,
     pub price_per_token: i128,
@@ -88,24 +259,181 @@ pub struct CampaignCreatedEvent {
 
 #[contractevent]
 pub struct InvestedEvent {
+    #[topic]
     pub campaign_id: u64,
+    #[topic]
     pub investor: Address,
     pub amount: i128,
     pub tokens_received: i128,
 }
 
+#[contractevent]
+pub struct InvestmentCommittedEvent {
+    pub campaign_id: u64,
+    pub investor: Address,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct CommitmentCancelledEvent {
+    pub campaign_id: u64,
+    pub investor: Address,
+    pub amount: i128,
+}
+
 #[contractevent]
 pub struct WithdrawnEvent {
+    #[topic]
     pub campaign_id: u64,
+    #[topic]
     pub company: Address,
     pub amount: i128,
 }
 
+#[contractevent]
+pub struct FeeCollectedEvent {
+    pub campaign_id: u64,
+    pub fee: i128,
+}
+
 #[contractevent]
 pub struct CampaignClosedEvent {
     pub campaign_id: u64,
 }
 
+#[contractevent]
+pub struct CapTableFinalizedEvent {
+    pub campaign_id: u64,
+    pub entries: Vec<(Address, i128)>, // Top investors by tokens held, largest first
+}
+
+#[contractevent]
+pub struct CampaignPublishedEvent {
+    pub campaign_id: u64,
+}
+
+#[contractevent]
+pub struct CampaignActivatedEvent {
+    pub campaign_id: u64,
+}
+
+#[contractevent]
+pub struct TokensDepositedEvent {
+    #[topic]
+    pub campaign_id: u64,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct CampaignReactivatedEvent {
+    pub campaign_id: u64,
+}
+
+#[contractevent]
+pub struct DeadlineExtendedEvent {
+    pub campaign_id: u64,
+    pub new_deadline: u64,
+}
+
+#[contractevent]
+pub struct CampaignLockedEvent {
+    pub campaign_id: u64,
+    pub investor_count: u32,
+}
+
+#[contractevent]
+pub struct InvestmentExitedEvent {
+    pub campaign_id: u64,
+    pub investor: Address,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct TokensClaimedEvent {
+    pub campaign_id: u64,
+    pub investor: Address,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct ReceiptReissuedEvent {
+    pub campaign_id: u64,
+    pub investor: Address,
+    pub receipt: BytesN<32>,
+}
+
+#[contractevent]
+pub struct RefundedEvent {
+    #[topic]
+    pub campaign_id: u64,
+    #[topic]
+    pub investor: Address,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct RedeemedEvent {
+    pub campaign_id: u64,
+    pub investor: Address,
+    pub token_amount: i128,
+    pub amount: i128,
+}
+
+#[contractevent]
+pub struct CampaignRepairedEvent {
+    pub campaign_id: u64,
+    pub raised_before: i128,
+    pub raised_after: i128,
+}
+
+#[contractevent]
+pub struct UnclaimedSweptEvent {
+    pub campaign_id: u64,
+    pub amount: i128,                 // Escrow swept to the admin
+    pub tokens: i128,                 // Unsold deposit returned to the company
+}
+
+#[contractevent]
+pub struct ResidualConvertedEvent {
+    pub campaign_id: u64,
+    pub amount: i128,                 // Escrow converted
+    pub tokens: i128,                 // Bonus tokens credited to investors
+}
+
+// -----------------------------
+// 🔗 EquityToken Interface
+// -----------------------------
+/// Mirror of the EquityToken's `CompanyInfo`
+#[derive(Clone)]
+#[contracttype]
+pub struct CompanyInfo {
+    pub name: String,
+    pub symbol: String,
+    pub total_supply: i128,
+    pub owner: Address,
+    pub equity_percent: i128,
+    pub description: String,
+    pub token_price: i128,
+    pub target_amount: i128,
+    pub clawback_enabled: bool,
+    pub price_tick: i128,
+    pub decimals: u32,
+}
+
+#[contractclient(name = "EquityTokenClient")]
+pub trait EquityTokenInterface {
+    fn balance_of(env: Env, addr: Address) -> i128;
+    fn get_company_info(env: Env) -> CompanyInfo;
+    fn burn(env: Env, from: Address, amount: i128);
+    fn get_burner(env: Env) -> Option<Address>;
+    fn burn_from(env: Env, burner: Address, from: Address, amount: i128);
+}
+
+#[contractclient(name = "TargetNotifyClient")]
+pub trait TargetNotifyInterface {
+    fn on_target_reached(env: Env, campaign_id: u64, raised_amount: i128);
+}
+
 // -----------------------------
 // 🗄️ Storage Keys
 // -----------------------------
@@ -113,6 +441,56 @@ const KEY_INITIALIZED: &str = "INIT";
 const KEY_ADMIN: &str = "ADMIN";
 const KEY_XLM_TOKEN: &str = "XLM";
 const KEY_STATS: &str = "STATS";
+const KEY_EVENT_LOG: &str = "EVLOG";
+const KEY_STATS_HISTORY: &str = "STATHIST";
+const KEY_REFUND_WINDOW: &str = "RFWIN";
+const KEY_LEADERBOARD: &str = "TOPINV";
+const KEY_MAX_SELLABLE: &str = "MAXSELL";
+const KEY_REACTIVATION_WINDOW: &str = "REACTWIN";
+const KEY_ALL_CAMPAIGNS: &str = "ALLCAMP";
+const KEY_FEE_BPS: &str = "FEE";
+const KEY_FEE_COLLECTOR: &str = "FEECOL";
+const KEY_RECORD_RAISE: &str = "RECORD";
+const KEY_NEXT_ID: &str = "NEXTID";
+const KEY_PAUSED: &str = "PAUSED";
+const KEY_REFUNDS_FROZEN: &str = "REFFRZ";
+const KEY_RAISE_TOKENS: &str = "RAISETOK";
+const KEY_LOCK: &str = "LOCK";
+
+// -----------------------------
+// 🔢 Constants
+// -----------------------------
+const BPS_DENOMINATOR: i128 = 10_000;
+const STROOPS_PER_XLM: i128 = 10_000_000;
+const SECONDS_PER_DAY: u64 = 86_400;
+const MAX_FEE_BPS: u32 = 1_000;        // Platform fee cap (10%)
+const COMMIT_WINDOW: u64 = SECONDS_PER_DAY; // Time to confirm a committed investment
+const RATE_SCALE: i128 = 10_000_000;   // Exchange rates carry 7 decimals (1.0 = 10,000,000)
+const MAX_EVENT_LOG: u32 = 10;         // Recent events kept for lightweight indexers
+const MAX_STATS_HISTORY: u32 = 30;     // Stats snapshots kept for trend charts
+const DEFAULT_REFUND_WINDOW: u64 = 30 * SECONDS_PER_DAY;
+const DEFAULT_REACTIVATION_WINDOW: u64 = SECONDS_PER_DAY;
+const MAX_LEADERBOARD: u32 = 10;       // Top investors tracked platform-wide
+const MAX_TAGS: u32 = 5;               // Tags per campaign
+const MAX_CAP_TABLE: u32 = 10;         // Cap table entries emitted at close (event size limit)
+const MAX_DURATION_MULTIPLE: u64 = 2;  // Extensions may stretch a campaign to this multiple of its original duration
+
+// Status codes reported by `describe_campaign`
+const STATUS_DRAFT: i128 = 0;          // Not yet published
+const STATUS_PENDING: i128 = 1;        // Published, awaiting activation
+const STATUS_OPEN: i128 = 2;           // Accepting investments
+const STATUS_SUCCEEDED: i128 = 3;      // Target reached, or soft cap met by the deadline
+const STATUS_FAILED: i128 = 4;         // Ended or closed below the soft cap
+
+// Investment phases reported by `get_current_phase`
+const PHASE_PRIVATE: u32 = 0;          // Whitelisted investors only
+const PHASE_PUBLIC: u32 = 1;           // Open to anyone
+const LEDGERS_PER_DAY: u32 = 17_280;   // ~5 second ledgers
+// Storage TTLs: entries are bumped to EXTEND_TO once their TTL falls below THRESHOLD
+const CAMPAIGN_TTL_THRESHOLD: u32 = 7 * LEDGERS_PER_DAY;
+const CAMPAIGN_TTL_EXTEND_TO: u32 = 30 * LEDGERS_PER_DAY;
+const INSTANCE_TTL_THRESHOLD: u32 = 7 * LEDGERS_PER_DAY;
+const INSTANCE_TTL_EXTEND_TO: u32 = 30 * LEDGERS_PER_DAY;
 
 // -----------------------------
 // ⚙️ Contract Implementation
@@ -125,13 +503,25 @@ impl FundraisingContract {
     // =============================
     
     /// Initialize the fundraising contract (one-time only)
-    pub fn initialize(env: Env, admin: Address, xlm_token_addr: Address) {
+    pub fn initialize(
+        env: Env,
+        admin: Address,
+        xlm_token_addr: Address,
+        fee_bps: u32,
+        fee_collector: Address,
+    ) {
         if env.storage().instance().has(&KEY_INITIALIZED) {
             panic!("Already initialized");
         }
 
         admin.require_auth();
 
+        if fee_bps > MAX_FEE_BPS {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+        env.storage().instance().set(&KEY_FEE_BPS, &fee_bps);
+        env.storage().instance().set(&KEY_FEE_COLLECTOR, &fee_collector);
+
         // Store admin and XLM token address
         env.storage().instance().set(&KEY_ADMIN, &admin);
         env.storage().instance().set(&KEY_XLM_TOKEN, &xlm_token_addr);
@@ -144,6 +534,9 @@ impl FundraisingContract {
             total_raised: 0,
         };
         env.storage().instance().set(&KEY_STATS, &stats);
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_TTL_THRESHOLD, INSTANCE_TTL_EXTEND_TO);
 
         InitEvent {
             admin: admin.clone(),
@@ -151,13 +544,114 @@ impl FundraisingContract {
         .publish(&env);
     }
 
-    // =============================
-    // 📋 CAMPAIGN MANAGEMENT
-    // =============================
-    
-    /// Create a new fundraising campaign
-    pub fn create_campaign(
+    /// Hand the admin role to a new address (current admin only)
+    pub fn transfer_admin(env: Env, new_admin: Address) -> Result<(), Error> {
+        Self::require_initialized(&env)?;
+        let old_admin: Address = env.storage().instance().get(&KEY_ADMIN).unwrap();
+        old_admin.require_auth();
+
+        env.storage().instance().set(&KEY_ADMIN, &new_admin);
+
+        AdminTransferredEvent {
+            old_admin,
+            new_admin,
+        }
+        .publish(&env);
+
+        Ok(())
+    }
+
+    /// Replace the contract code, keeping all stored state (admin only)
+    /// The new wasm must already be uploaded to the network
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<(), Error> {
+        Self::require_initialized(&env)?;
+        let admin: Address = env.storage().instance().get(&KEY_ADMIN).unwrap();
+        admin.require_auth();
+
+        env.deployer().update_current_contract_wasm(new_wasm_hash.clone());
+        UpgradedEvent { new_wasm_hash }.publish(&env);
+
+        Ok(())
+    }
+
+    /// Pause or resume investing and withdrawals platform-wide (admin only)
+    /// Queries, refunds and campaign management stay available while paused
+    pub fn set_paused(env: Env, paused: bool) {
+        Self::require_initialized(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+        let admin: Address = env.storage().instance().get(&KEY_ADMIN).unwrap();
+        admin.require_auth();
+
+        if Self::is_paused(env.clone()) != paused {
+            env.storage().instance().set(&KEY_PAUSED, &paused);
+            PausedEvent { paused }.publish(&env);
+        }
+    }
+
+    /// Whether investing and withdrawals are paused
+    pub fn is_paused(env: Env) -> bool {
+        env.storage().instance().get(&KEY_PAUSED).unwrap_or(false)
+    }
+
+    /// Freeze or unfreeze refunds platform-wide while a dispute is settled (admin only)
+    /// Independent of `set_paused`, which leaves refunds available
+    pub fn set_refunds_frozen(env: Env, frozen: bool) {
+        Self::require_initialized(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+        let admin: Address = env.storage().instance().get(&KEY_ADMIN).unwrap();
+        admin.require_auth();
+
+        if Self::is_refunds_frozen(env.clone()) != frozen {
+            env.storage().instance().set(&KEY_REFUNDS_FROZEN, &frozen);
+            RefundsFrozenEvent { frozen }.publish(&env);
+        }
+    }
+
+    /// Whether refunds are frozen
+    pub fn is_refunds_frozen(env: Env) -> bool {
+        env.storage().instance().get(&KEY_REFUNDS_FROZEN).unwrap_or(false)
+    }
+
+    /// Allow or disallow campaigns to raise in `token` (admin only)
+    /// The platform XLM token is always allowed
+    pub fn set_raise_token_allowed(env: Env, token: Address, allowed: bool) {
+        Self::require_initialized(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+        let admin: Address = env.storage().instance().get(&KEY_ADMIN).unwrap();
+        admin.require_auth();
+
+        let mut tokens = Self::get_raise_tokens(env.clone());
+        match (tokens.first_index_of(&token), allowed) {
+            (None, true) => tokens.push_back(token),
+            (Some(index), false) => {
+                tokens.remove(index);
+            }
+            _ => return,
+        }
+        env.storage().instance().set(&KEY_RAISE_TOKENS, &tokens);
+    }
+
+    /// Get the admin-approved raise tokens (besides the platform XLM token)
+    pub fn get_raise_tokens(env: Env) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .get(&KEY_RAISE_TOKENS)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Whether campaigns may raise in `token`
+    pub fn is_raise_token_allowed(env: Env, token: Address) -> bool {
+        let xlm_token_addr: Address = env.storage().instance().get(&KEY_XLM_TOKEN).unwrap();
+        token == xlm_token_addr || Self::get_raise_tokens(env).contains(&token)
+    }
+
+    /// Get the current admin
+    pub fn get_admin(env: Env) -> Address {
+        env.storage().instance().get(&KEY_ADMIN).unwrap()
+    }
+
+    /// Initialize the contract and create its first campaign in one transaction
+    pub fn initialize_and_create(
         env: Env,
+        admin: Address,
+        xlm_token_addr: Address,
         campaign_id: u64,
         company_addr: Address,
         equity_token_addr: Address,
@@ -167,39 +661,192 @@ impl FundraisingContract {
         min_investment: i128,
         max_investment: i128,
     ) {
-        Self::require_initialized(&env);
-        company_addr.require_auth();
+        // `initialize` panics if already initialized, so this can only run once
+        // Soroban caps entrypoints at 10 arguments, so the soft cap defaults to the target
+        // and fees start at zero, collected by the admin until `set_fee_collector`
+        Self::initialize(env.clone(), admin.clone(), xlm_token_addr, 0, admin);
+        Self::create_campaign(
+            env,
+            campaign_id,
+            company_addr,
+            equity_token_addr,
+            target_amount,
+            price_per_token,
+            deadline,
+            min_investment,
+            max_investment,
+            target_amount,
+        );
+    }
 
-        // Validations
-        if target_amount <= 0 || price_per_token <= 0 || min_investment <= 0 {
-            panic!("Invalid amount");
-        }
+    /// Cap the share of company equity (bps) a single campaign may sell (admin only)
+    pub fn set_max_sellable_bps(env: Env, max_sellable_bps: i128) {
+        Self::require_initialized(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+        let admin: Address = env.storage().instance().get(&KEY_ADMIN).unwrap();
+        admin.require_auth();
 
-        if deadline <= env.ledger().timestamp() {
-            panic!("Deadline invalid");
+        if max_sellable_bps <= 0 || max_sellable_bps > BPS_DENOMINATOR {
+            panic_with_error!(&env, Error::InvalidAmount);
         }
+        env.storage().instance().set(&KEY_MAX_SELLABLE, &max_sellable_bps);
+    }
 
-        if max_investment > 0 && max_investment < min_investment {
-            panic!("Invalid amount");
-        }
+    /// Set the global platform fee taken from withdrawals, in bps (admin only, max 10%)
+    pub fn set_fee(env: Env, fee_bps: u32) {
+        Self::require_initialized(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+        let admin: Address = env.storage().instance().get(&KEY_ADMIN).unwrap();
+        admin.require_auth();
 
-        // Check if campaign ID already exists
-        let campaign_key = Self::get_campaign_key(campaign_id);
-        if env.storage().persistent().has(&campaign_key) {
-            panic!("Campaign exists");
+        if fee_bps > MAX_FEE_BPS {
+            panic_with_error!(&env, Error::InvalidAmount);
         }
+        env.storage().instance().set(&KEY_FEE_BPS, &fee_bps);
+    }
 
-        // Create campaign
-        let campaign = Campaign {
-            company_addr: company_addr.clone(),
+    /// Set the address platform fees are paid to (admin only)
+    pub fn set_fee_collector(env: Env, fee_collector: Address) {
+        Self::require_initialized(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+        let admin: Address = env.storage().instance().get(&KEY_ADMIN).unwrap();
+        admin.require_auth();
+
+        env.storage().instance().set(&KEY_FEE_COLLECTOR, &fee_collector);
+    }
+
+    /// Set (or clear) a negotiated fee for one campaign, taking precedence over the
+    /// global fee (admin only, max 10%)
+    pub fn set_campaign_fee(env: Env, campaign_id: u64, fee_bps: Option<u32>) {
+        Self::require_initialized(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+        let admin: Address = env.storage().instance().get(&KEY_ADMIN).unwrap();
+        admin.require_auth();
+
+        if fee_bps.is_some_and(|bps| bps > MAX_FEE_BPS) {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+
+        let campaign_key = Self::get_campaign_key(campaign_id);
+        let mut campaign = Self::get_campaign(env.clone(), campaign_id);
+        campaign.fee_bps_override = fee_bps;
+        env.storage().persistent().set(&campaign_key, &campaign);
+    }
+
+    /// Set the rate converting the raise token into the XLM terms equity is priced in
+    /// (admin only, scaled by 10,000,000). Can be updated at any time, e.g. by an oracle feed.
+    pub fn set_exchange_rate(env: Env, campaign_id: u64, rate: i128) {
+        Self::require_initialized(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+        let admin: Address = env.storage().instance().get(&KEY_ADMIN).unwrap();
+        admin.require_auth();
+
+        if rate <= 0 {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+        // Ensure the campaign exists
+        Self::get_campaign(env.clone(), campaign_id);
+
+        env.storage()
+            .persistent()
+            .set(&Self::get_exchange_rate_key(campaign_id), &rate);
+    }
+
+    // =============================
+    // 📋 CAMPAIGN MANAGEMENT
+    // =============================
+    
+    /// Create a new fundraising campaign (starts as an unpublished, inactive draft)
+    pub fn create_campaign(
+        env: Env,
+        campaign_id: u64,
+        company_addr: Address,
+        equity_token_addr: Address,
+        target_amount: i128,
+        price_per_token: i128,
+        deadline: u64,
+        min_investment: i128,
+        max_investment: i128,
+        soft_cap: i128,
+    ) {
+        Self::require_initialized(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+        company_addr.require_auth();
+
+        // Validations
+        if target_amount <= 0 || price_per_token <= 0 || min_investment <= 0 {
+            panic!("Invalid amount");
+        }
+
+        if deadline <= env.ledger().timestamp() {
+            panic!("Deadline invalid");
+        }
+
+        if max_investment > 0 && max_investment < min_investment {
+            panic!("Invalid amount");
+        }
+
+        if soft_cap <= 0 || soft_cap > target_amount {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+
+        // The smallest allowed investment must buy at least one token
+        if min_investment < price_per_token {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+
+        // Company must retain equity beyond what a single campaign may sell
+        if let Some(max_sellable_bps) = Self::get_max_sellable_bps(env.clone()) {
+            let info = EquityTokenClient::new(&env, &equity_token_addr).get_company_info();
+            let tokens_for_sale = target_amount / price_per_token;
+            // Burns can shrink total_supply, so it is checked like the products
+            let equity_for_sale_bps = tokens_for_sale
+                .checked_mul(info.equity_percent)
+                .and_then(|v| v.checked_mul(100))
+                .and_then(|v| v.checked_div(info.total_supply))
+                .unwrap_or_else(|| panic_with_error!(&env, Error::Overflow));
+            if equity_for_sale_bps > max_sellable_bps {
+                panic_with_error!(&env, Error::EquityCapExceeded);
+            }
+        }
+
+        // Check if campaign ID already exists
+        let campaign_key = Self::get_campaign_key(campaign_id);
+        if env.storage().persistent().has(&campaign_key) {
+            panic!("Campaign exists");
+        }
+
+        // Create campaign
+        let campaign = Campaign {
+            company_addr: company_addr.clone(),
             equity_token_addr: equity_token_addr.clone(),
             target_amount,
             price_per_token,
             raised_amount: 0,
-            is_active: true,
+            is_active: false,
             deadline,
             min_investment,
             max_investment,
+            created_at: env.ledger().timestamp(),
+            withdrawn_amount: 0,
+            published: false,
+            refunded_amount: 0,
+            refund_deadline: 0,
+            max_investments_per_investor: 0,
+            notify_contract: None,
+            activated_at: 0,
+            tags: Vec::new(&env),
+            closed_at: 0,
+            redeemable: false,
+            redeemed_amount: 0,
+            max_tokens_per_investor: 0,
+            min_investors: 0,
+            locked: false,
+            fee_bps_override: None,
+            soft_cap,
+            private_deadline: 0,
+            auto_withdraw: false,
+            payment_token: None,
+            original_deadline: deadline,
+            max_investors: 0,
+            requires_whitelist: false,
+            auto_convert_residual: false,
+            parent_campaign_id: None,
+            allow_oversubscription: false,
         };
 
         // Store campaign
@@ -210,304 +857,3148 @@ impl FundraisingContract {
         let investors_key = Self::get_investors_key(campaign_id);
         env.storage().persistent().set(&investors_key, &investors);
 
+        // Index campaign under its company
+        let company_key = Self::get_company_campaigns_key(&company_addr);
+        let mut company_campaigns: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&company_key)
+            .unwrap_or(Vec::new(&env));
+        company_campaigns.push_back(campaign_id);
+        env.storage().persistent().set(&company_key, &company_campaigns);
+
+        // Register campaign in the platform-wide index
+        let mut all_campaigns: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&KEY_ALL_CAMPAIGNS)
+            .unwrap_or(Vec::new(&env));
+        all_campaigns.push_back(campaign_id);
+        env.storage().persistent().set(&KEY_ALL_CAMPAIGNS, &all_campaigns);
+        Self::bump_campaign_ttl(&env, campaign_id);
+
         // Update stats
         let mut stats: CampaignStats = env.storage().instance().get(&KEY_STATS).unwrap();
         stats.total_campaigns += 1;
+        env.storage().instance().set(&KEY_STATS, &stats);
+
+        // Emit event
+        Self::record_event(&env, symbol_short!("created"), campaign_id, target_amount);
+        CampaignCreatedEvent {
+            campaign_id,
+            company: company_addr.clone(),
+            target_amount,
+            price_per_token,
+            deadline,
+        }
+        .publish(&env);
+    }
+
+    /// Create a campaign under the next free id and return that id
+    pub fn create_campaign_auto(
+        env: Env,
+        company_addr: Address,
+        equity_token_addr: Address,
+        target_amount: i128,
+        price_per_token: i128,
+        deadline: u64,
+        min_investment: i128,
+        max_investment: i128,
+        soft_cap: i128,
+    ) -> u64 {
+        let campaign_id = Self::get_next_campaign_id(env.clone());
+
+        Self::create_campaign(
+            env.clone(),
+            campaign_id,
+            company_addr,
+            equity_token_addr,
+            target_amount,
+            price_per_token,
+            deadline,
+            min_investment,
+            max_investment,
+            soft_cap,
+        );
+
+        env.storage().instance().set(&KEY_NEXT_ID, &(campaign_id + 1));
+        campaign_id
+    }
+
+    /// Id `create_campaign_auto` will assign next, skipping ids already taken explicitly
+    pub fn get_next_campaign_id(env: Env) -> u64 {
+        let mut campaign_id: u64 = env.storage().instance().get(&KEY_NEXT_ID).unwrap_or(1);
+        while env.storage().persistent().has(&Self::get_campaign_key(campaign_id)) {
+            campaign_id += 1;
+        }
+        campaign_id
+    }
+
+    /// Publish a draft campaign so it can be discovered and invested in (company only)
+    pub fn publish_campaign(env: Env, campaign_id: u64) {
+        Self::require_initialized(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+
+        let campaign_key = Self::get_campaign_key(campaign_id);
+        let mut campaign: Campaign = env
+            .storage()
+            .persistent()
+            .get(&campaign_key)
+            .unwrap_or_else(|| panic!("Campaign not found"));
+
+        campaign.company_addr.require_auth();
+
+        if !campaign.published {
+            campaign.published = true;
+            env.storage().persistent().set(&campaign_key, &campaign);
+
+            CampaignPublishedEvent { campaign_id }.publish(&env);
+        }
+    }
+
+    /// Deposit equity tokens backing a campaign (company only)
+    /// Deposits are credited to this campaign alone, so rounds sharing a token never share them
+    pub fn deposit_tokens(env: Env, campaign_id: u64, amount: i128) {
+        Self::require_initialized(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+        Self::non_reentrant(&env, || {
+            let campaign: Campaign = env
+                .storage()
+                .persistent()
+                .get(&Self::get_campaign_key(campaign_id))
+                .ok_or(Error::CampaignNotFound)?;
+            campaign.company_addr.require_auth();
+
+            if amount <= 0 {
+                return Err(Error::InvalidAmount);
+            }
+
+            let deposit_key = Self::get_token_deposit_key(campaign_id);
+            let deposited: i128 = env.storage().persistent().get(&deposit_key).unwrap_or(0);
+            let deposited = deposited.checked_add(amount).ok_or(Error::Overflow)?;
+
+            let contract_addr = env.current_contract_address();
+            token::Client::new(&env, &campaign.equity_token_addr).transfer(
+                &campaign.company_addr,
+                &contract_addr,
+                &amount,
+            );
+            env.storage().persistent().set(&deposit_key, &deposited);
+
+            TokensDepositedEvent {
+                campaign_id,
+                amount,
+            }
+            .publish(&env);
+
+            Ok(())
+        })
+        .unwrap_or_else(|e| panic_with_error!(&env, e));
+    }
+
+    /// Get the equity tokens deposited for a campaign
+    pub fn get_token_deposit(env: Env, campaign_id: u64) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&Self::get_token_deposit_key(campaign_id))
+            .unwrap_or(0)
+    }
+
+    /// Activate a campaign once the company has deposited enough equity tokens (company only)
+    /// The campaign's own deposit must cover selling the full target at its pricing; each
+    /// sale after that is still reserved against the deposit
+    pub fn activate_campaign(env: Env, campaign_id: u64) {
+        Self::require_initialized(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+
+        let campaign_key = Self::get_campaign_key(campaign_id);
+        let mut campaign = Self::get_campaign(env.clone(), campaign_id);
+        campaign.company_addr.require_auth();
+
+        if campaign.is_active || campaign.activated_at != 0 {
+            panic_with_error!(&env, Error::CampaignAlreadyActivated);
+        }
+
+        if env.ledger().timestamp() > campaign.deadline {
+            panic!("Deadline passed");
+        }
+
+        // The raise token may have been delisted since it was chosen in draft
+        if !Self::is_raise_token_allowed(env.clone(), Self::payment_token(&env, &campaign)) {
+            panic_with_error!(&env, Error::RaiseTokenNotAllowed);
+        }
+
+        let tokens_required = Self::tokens_required(&env, campaign_id, &campaign);
+        if Self::get_token_deposit(env.clone(), campaign_id) < tokens_required {
+            panic_with_error!(&env, Error::InsufficientBalance);
+        }
+
+        campaign.is_active = true;
+        campaign.activated_at = env.ledger().timestamp();
+        env.storage().persistent().set(&campaign_key, &campaign);
+
+        // Update stats
+        let mut stats: CampaignStats = env.storage().instance().get(&KEY_STATS).unwrap();
         stats.active_campaigns += 1;
         env.storage().instance().set(&KEY_STATS, &stats);
 
-        // Emit event
-        CampaignCreatedEvent {
-            campaign_id,
-            company: company_addr.clone(),
-            target_amount,
-            price_per_token,
-            deadline,
+        Self::record_event(&env, symbol_short!("activated"), campaign_id, 0);
+        CampaignActivatedEvent { campaign_id }.publish(&env);
+    }
+
+    /// Replace a draft campaign's tags and update the per-tag indexes (company only)
+    pub fn set_campaign_tags(env: Env, campaign_id: u64, tags: Vec<Symbol>) {
+        Self::require_initialized(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+
+        let campaign_key = Self::get_campaign_key(campaign_id);
+        let mut campaign = Self::get_campaign(env.clone(), campaign_id);
+        campaign.company_addr.require_auth();
+        Self::require_draft(&env, &campaign);
+
+        if tags.len() > MAX_TAGS {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+
+        // Drop the campaign from its old tag indexes
+        for tag in campaign.tags.iter() {
+            let tag_key = Self::get_tag_key(&tag);
+            let mut tagged: Vec<u64> = env.storage().persistent().get(&tag_key).unwrap_or(Vec::new(&env));
+            if let Some(pos) = tagged.first_index_of(campaign_id) {
+                tagged.remove(pos);
+                env.storage().persistent().set(&tag_key, &tagged);
+            }
+        }
+
+        let mut unique_tags: Vec<Symbol> = Vec::new(&env);
+        for tag in tags.iter() {
+            if unique_tags.contains(&tag) {
+                continue;
+            }
+            let tag_key = Self::get_tag_key(&tag);
+            let mut tagged: Vec<u64> = env.storage().persistent().get(&tag_key).unwrap_or(Vec::new(&env));
+            tagged.push_back(campaign_id);
+            env.storage().persistent().set(&tag_key, &tagged);
+            unique_tags.push_back(tag);
+        }
+
+        campaign.tags = unique_tags;
+        env.storage().persistent().set(&campaign_key, &campaign);
+    }
+
+    /// Set tiered pricing for a draft campaign (company only)
+    /// Raises beyond the last tier use the campaign's base `price_per_token`
+    pub fn set_price_tiers(env: Env, campaign_id: u64, tiers: Vec<PriceTier>) {
+        Self::require_initialized(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+
+        let campaign = Self::get_campaign(env.clone(), campaign_id);
+        campaign.company_addr.require_auth();
+        Self::require_draft(&env, &campaign);
+
+        // Tiers must be ordered by strictly increasing thresholds
+        let mut prev_up_to: i128 = 0;
+        for tier in tiers.iter() {
+            if tier.up_to <= prev_up_to || tier.price_per_token <= 0 {
+                panic_with_error!(&env, Error::InvalidAmount);
+            }
+            prev_up_to = tier.up_to;
+        }
+
+        env.storage()
+            .persistent()
+            .set(&Self::get_tiers_key(campaign_id), &tiers);
+    }
+
+    /// Decay the token price linearly from `start_price` to `floor_price` by the deadline
+    /// (company only, draft). Takes precedence over price tiers.
+    pub fn set_price_decay(env: Env, campaign_id: u64, start_price: i128, floor_price: i128) {
+        Self::require_initialized(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+
+        let campaign = Self::get_campaign(env.clone(), campaign_id);
+        campaign.company_addr.require_auth();
+        Self::require_draft(&env, &campaign);
+
+        if floor_price <= 0 || start_price < floor_price {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+
+        env.storage().persistent().set(
+            &Self::get_decay_key(campaign_id),
+            &PriceDecay {
+                start_price,
+                floor_price,
+            },
+        );
+    }
+
+    /// Shrink the per-investor cap linearly from `start_cap` to `end_cap` as the raise
+    /// fills (company only, draft). `max_investment`, if set, still applies on top.
+    pub fn set_progressive_cap(env: Env, campaign_id: u64, start_cap: i128, end_cap: i128) {
+        Self::require_initialized(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+
+        let campaign = Self::get_campaign(env.clone(), campaign_id);
+        campaign.company_addr.require_auth();
+        Self::require_draft(&env, &campaign);
+
+        if end_cap < campaign.min_investment || start_cap < end_cap {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+
+        env.storage().persistent().set(
+            &Self::get_progressive_cap_key(campaign_id),
+            &ProgressiveCap { start_cap, end_cap },
+        );
+    }
+
+    /// Release investors' tokens on a cliff plus linear vesting schedule (company only, draft)
+    /// Unvested tokens stay in the contract until `claim_tokens` can release them
+    pub fn set_vesting_schedule(env: Env, campaign_id: u64, schedule: VestingSchedule) {
+        Self::require_initialized(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+
+        let campaign = Self::get_campaign(env.clone(), campaign_id);
+        campaign.company_addr.require_auth();
+        Self::require_draft(&env, &campaign);
+
+        if schedule.duration == 0 || schedule.cliff > schedule.duration {
+            panic_with_error!(&env, Error::DeadlineInvalid);
+        }
+
+        env.storage()
+            .persistent()
+            .set(&Self::get_vesting_key(campaign_id), &schedule);
+    }
+
+    /// Pay a secondary bonus token alongside equity as it is claimed (company only, draft)
+    pub fn set_bonus_token(env: Env, campaign_id: u64, token_addr: Address, ratio_bps: i128) {
+        Self::require_initialized(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+
+        let campaign = Self::get_campaign(env.clone(), campaign_id);
+        campaign.company_addr.require_auth();
+        Self::require_draft(&env, &campaign);
+
+        if ratio_bps <= 0 {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+
+        env.storage().persistent().set(
+            &Self::get_bonus_key(campaign_id),
+            &BonusToken { token_addr, ratio_bps },
+        );
+    }
+
+    /// Split withdrawn funds between recipients by bps share (company only, draft)
+    /// Shares must sum to 10,000 bps; an empty list sends everything to the company
+    pub fn set_withdraw_splits(env: Env, campaign_id: u64, splits: Vec<(Address, i128)>) {
+        Self::require_initialized(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+
+        let campaign = Self::get_campaign(env.clone(), campaign_id);
+        campaign.company_addr.require_auth();
+        Self::require_draft(&env, &campaign);
+
+        if !splits.is_empty() {
+            let mut total_bps: i128 = 0;
+            for (_, bps) in splits.iter() {
+                if bps <= 0 {
+                    panic_with_error!(&env, Error::InvalidAmount);
+                }
+                total_bps = total_bps
+                    .checked_add(bps)
+                    .unwrap_or_else(|| panic_with_error!(&env, Error::Overflow));
+            }
+            if total_bps != BPS_DENOMINATOR {
+                panic_with_error!(&env, Error::InvalidAmount);
+            }
+        }
+
+        env.storage()
+            .persistent()
+            .set(&Self::get_splits_key(campaign_id), &splits);
+    }
+
+    /// Limit how many separate investments one address can make (company only, draft)
+    pub fn set_max_investments_per_investor(env: Env, campaign_id: u64, max_investments: u32) {
+        Self::require_initialized(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+
+        let campaign_key = Self::get_campaign_key(campaign_id);
+        let mut campaign = Self::get_campaign(env.clone(), campaign_id);
+        campaign.company_addr.require_auth();
+        Self::require_draft(&env, &campaign);
+
+        campaign.max_investments_per_investor = max_investments;
+        env.storage().persistent().set(&campaign_key, &campaign);
+    }
+
+    /// Allow investors to burn equity tokens for escrowed XLM (company only, draft)
+    pub fn set_redeemable(env: Env, campaign_id: u64, redeemable: bool) {
+        Self::require_initialized(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+
+        let campaign_key = Self::get_campaign_key(campaign_id);
+        let mut campaign = Self::get_campaign(env.clone(), campaign_id);
+        campaign.company_addr.require_auth();
+        Self::require_draft(&env, &campaign);
+
+        campaign.redeemable = redeemable;
+        env.storage().persistent().set(&campaign_key, &campaign);
+    }
+
+    /// Cap the equity tokens a single investor may receive (company only, draft, 0 = no limit)
+    pub fn set_max_tokens_per_investor(env: Env, campaign_id: u64, max_tokens: i128) {
+        Self::require_initialized(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+
+        let campaign_key = Self::get_campaign_key(campaign_id);
+        let mut campaign = Self::get_campaign(env.clone(), campaign_id);
+        campaign.company_addr.require_auth();
+        Self::require_draft(&env, &campaign);
+
+        if max_tokens < 0 {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+
+        campaign.max_tokens_per_investor = max_tokens;
+        env.storage().persistent().set(&campaign_key, &campaign);
+    }
+
+    /// Keep investments freely refundable until `min_investors` distinct investors have
+    /// joined (company only, draft, 0 = binding at once)
+    pub fn set_min_investors(env: Env, campaign_id: u64, min_investors: u32) {
+        Self::require_initialized(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+
+        let campaign_key = Self::get_campaign_key(campaign_id);
+        let mut campaign = Self::get_campaign(env.clone(), campaign_id);
+        campaign.company_addr.require_auth();
+        Self::require_draft(&env, &campaign);
+
+        if campaign.max_investors > 0 && min_investors > campaign.max_investors {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+
+        campaign.min_investors = min_investors;
+        env.storage().persistent().set(&campaign_key, &campaign);
+    }
+
+    /// Cap the number of distinct investors, e.g. 99 for some private offerings
+    /// (company only, draft, 0 = no limit). Existing investors may always top up
+    pub fn set_max_investors(env: Env, campaign_id: u64, max_investors: u32) {
+        Self::require_initialized(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+
+        let campaign_key = Self::get_campaign_key(campaign_id);
+        let mut campaign = Self::get_campaign(env.clone(), campaign_id);
+        campaign.company_addr.require_auth();
+        Self::require_draft(&env, &campaign);
+
+        if max_investors > 0 && max_investors < campaign.min_investors {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+
+        campaign.max_investors = max_investors;
+        env.storage().persistent().set(&campaign_key, &campaign);
+    }
+
+    /// Open the campaign to whitelisted investors only until `private_deadline`, then to
+    /// everyone (company only, draft, 0 = public from the start)
+    pub fn set_private_phase(
+        env: Env,
+        campaign_id: u64,
+        private_deadline: u64,
+        whitelist: Vec<Address>,
+    ) {
+        Self::require_initialized(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+
+        let campaign_key = Self::get_campaign_key(campaign_id);
+        let mut campaign = Self::get_campaign(env.clone(), campaign_id);
+        campaign.company_addr.require_auth();
+        Self::require_draft(&env, &campaign);
+
+        if private_deadline >= campaign.deadline {
+            panic_with_error!(&env, Error::DeadlineInvalid);
+        }
+
+        for investor in whitelist.iter() {
+            env.storage()
+                .persistent()
+                .set(&Self::get_whitelist_key(campaign_id, &investor), &true);
+        }
+
+        campaign.private_deadline = private_deadline;
+        env.storage().persistent().set(&campaign_key, &campaign);
+    }
+
+    /// Restrict investing to whitelisted investors for the whole campaign (company only, draft)
+    pub fn set_requires_whitelist(env: Env, campaign_id: u64, requires_whitelist: bool) {
+        Self::require_initialized(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+
+        let campaign_key = Self::get_campaign_key(campaign_id);
+        let mut campaign = Self::get_campaign(env.clone(), campaign_id);
+        campaign.company_addr.require_auth();
+        Self::require_draft(&env, &campaign);
+
+        campaign.requires_whitelist = requires_whitelist;
+        env.storage().persistent().set(&campaign_key, &campaign);
+    }
+
+    /// Add or remove an investor from the campaign whitelist, e.g. once KYC clears (company only)
+    pub fn set_investor_whitelisted(env: Env, campaign_id: u64, investor: Address, allowed: bool) {
+        Self::require_initialized(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+
+        let campaign = Self::get_campaign(env.clone(), campaign_id);
+        campaign.company_addr.require_auth();
+
+        let whitelist_key = Self::get_whitelist_key(campaign_id, &investor);
+        if allowed {
+            env.storage().persistent().set(&whitelist_key, &true);
+        } else {
+            env.storage().persistent().remove(&whitelist_key);
+        }
+    }
+
+    /// Whether an investor is on the campaign whitelist (private phase and KYC gate)
+    pub fn is_whitelisted(env: Env, campaign_id: u64, investor: Address) -> bool {
+        env.storage()
+            .persistent()
+            .get(&Self::get_whitelist_key(campaign_id, &investor))
+            .unwrap_or(false)
+    }
+
+    /// Current investment phase: 0 = private (whitelist only), 1 = public
+    pub fn get_current_phase(env: Env, campaign_id: u64) -> u32 {
+        let campaign = Self::get_campaign(env.clone(), campaign_id);
+        if env.ledger().timestamp() < campaign.private_deadline {
+            PHASE_PRIVATE
+        } else {
+            PHASE_PUBLIC
+        }
+    }
+
+    /// Withdraw to the company and close the campaign on the investment that reaches the
+    /// target (company only, draft)
+    pub fn set_auto_withdraw(env: Env, campaign_id: u64, auto_withdraw: bool) {
+        Self::require_initialized(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+
+        let campaign_key = Self::get_campaign_key(campaign_id);
+        let mut campaign = Self::get_campaign(env.clone(), campaign_id);
+        campaign.company_addr.require_auth();
+        Self::require_draft(&env, &campaign);
+
+        campaign.auto_withdraw = auto_withdraw;
+        env.storage().persistent().set(&campaign_key, &campaign);
+    }
+
+    /// When a successful campaign is closed, credit investors bonus equity for the escrow still
+    /// held, pro rata to their investment, then release it to the company (company only, draft)
+    pub fn set_auto_convert_residual(env: Env, campaign_id: u64, auto_convert_residual: bool) {
+        Self::require_initialized(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+
+        let campaign_key = Self::get_campaign_key(campaign_id);
+        let mut campaign = Self::get_campaign(env.clone(), campaign_id);
+        campaign.company_addr.require_auth();
+        Self::require_draft(&env, &campaign);
+
+        campaign.auto_convert_residual = auto_convert_residual;
+        env.storage().persistent().set(&campaign_key, &campaign);
+    }
+
+    /// Let investments push the raise past the target (company only, draft)
+    /// By default the target is a hard cap and the last investment is trimmed to fit
+    pub fn set_allow_oversubscription(env: Env, campaign_id: u64, allow_oversubscription: bool) {
+        Self::require_initialized(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+
+        let campaign_key = Self::get_campaign_key(campaign_id);
+        let mut campaign = Self::get_campaign(env.clone(), campaign_id);
+        campaign.company_addr.require_auth();
+        Self::require_draft(&env, &campaign);
+
+        campaign.allow_oversubscription = allow_oversubscription;
+        env.storage().persistent().set(&campaign_key, &campaign);
+    }
+
+    /// Link a follow-on round to the company's previous campaign (company only, draft)
+    pub fn set_parent_campaign(env: Env, campaign_id: u64, parent_campaign_id: Option<u64>) {
+        Self::require_initialized(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+
+        let campaign_key = Self::get_campaign_key(campaign_id);
+        let mut campaign = Self::get_campaign(env.clone(), campaign_id);
+        campaign.company_addr.require_auth();
+        Self::require_draft(&env, &campaign);
+
+        // The parent must be another campaign of the same company, and linking must not loop
+        if let Some(parent_id) = parent_campaign_id {
+            if !env.storage().persistent().has(&Self::get_campaign_key(parent_id))
+                || Self::get_campaign(env.clone(), parent_id).company_addr != campaign.company_addr
+                || Self::get_campaign_lineage(env.clone(), parent_id).contains(campaign_id)
+            {
+                panic_with_error!(&env, Error::InvalidParentCampaign);
+            }
+        }
+
+        campaign.parent_campaign_id = parent_campaign_id;
+        env.storage().persistent().set(&campaign_key, &campaign);
+    }
+
+    /// Raise in an admin-approved token instead of the platform XLM token
+    /// (company only, draft, None = XLM)
+    pub fn set_payment_token(env: Env, campaign_id: u64, payment_token: Option<Address>) {
+        Self::require_initialized(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+
+        let campaign_key = Self::get_campaign_key(campaign_id);
+        let mut campaign = Self::get_campaign(env.clone(), campaign_id);
+        campaign.company_addr.require_auth();
+        Self::require_draft(&env, &campaign);
+
+        if let Some(token) = &payment_token {
+            if !Self::is_raise_token_allowed(env.clone(), token.clone()) {
+                panic_with_error!(&env, Error::RaiseTokenNotAllowed);
+            }
+        }
+
+        campaign.payment_token = payment_token;
+        env.storage().persistent().set(&campaign_key, &campaign);
+    }
+
+    /// Set (or clear) the contract notified when the target is reached (company only, draft)
+    pub fn set_notify_contract(env: Env, campaign_id: u64, notify_contract: Option<Address>) {
+        Self::require_initialized(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+
+        let campaign_key = Self::get_campaign_key(campaign_id);
+        let mut campaign = Self::get_campaign(env.clone(), campaign_id);
+        campaign.company_addr.require_auth();
+        Self::require_draft(&env, &campaign);
+
+        campaign.notify_contract = notify_contract;
+        env.storage().persistent().set(&campaign_key, &campaign);
+    }
+
+    // =============================
+    // 💰 INVESTMENT FUNCTIONS
+    // =============================
+    
+    /// Invest in a campaign
+    /// Only what the campaign can still accept is taken, unless `fill_or_kill` is set,
+    /// in which case anything short of a full fill is rejected
+    pub fn invest(
+        env: Env,
+        campaign_id: u64,
+        investor: Address,
+        amount: i128,
+        fill_or_kill: bool,
+    ) -> Result<(), Error> {
+        Self::require_initialized(&env)?;
+        Self::non_reentrant(&env, || {
+            Self::require_not_paused(&env)?;
+            investor.require_auth();
+
+            if amount <= 0 {
+                return Err(Error::InvalidAmount);
+            }
+
+            // Load campaign
+            let campaign: Campaign = env
+                .storage()
+                .persistent()
+                .get(&Self::get_campaign_key(campaign_id))
+                .ok_or(Error::CampaignNotFound)?;
+
+            if Self::is_fully_subscribed(&campaign) {
+                return Err(Error::CampaignInactive);
+            }
+
+            let accepted = amount.min(Self::investable_amount(&env, campaign_id, &campaign, &investor));
+            if accepted < amount && (fill_or_kill || accepted <= 0) {
+                return Err(Error::InvestmentTooLarge);
+            }
+            let amount = accepted;
+
+            Self::check_investment(&env, campaign_id, &campaign, &investor, amount)?;
+
+            // Transfer XLM from investor to contract
+            // This is the first mutation, so a failed transfer leaves no partial state behind
+            let payment_token = token::Client::new(&env, &Self::payment_token(&env, &campaign));
+            let contract_addr = env.current_contract_address();
+
+            if !matches!(payment_token.try_transfer(&investor, &contract_addr, &amount), Ok(Ok(()))) {
+                return Err(Error::TransferFailed);
+            }
+
+            Self::record_investment(&env, campaign_id, campaign, &investor, amount)
+        })
+    }
+
+    /// Invest in several campaigns at once; any failing leg reverts the whole batch
+    pub fn invest_batch(
+        env: Env,
+        investor: Address,
+        campaign_ids: Vec<u64>,
+        amounts: Vec<i128>,
+    ) -> Result<(), Error> {
+        Self::require_initialized(&env)?;
+        Self::non_reentrant(&env, || {
+            Self::require_not_paused(&env)?;
+            investor.require_auth();
+
+            if campaign_ids.is_empty() || campaign_ids.len() != amounts.len() {
+                return Err(Error::InvalidAmount);
+            }
+
+            // Validate every leg before moving any funds
+            for (campaign_id, amount) in campaign_ids.iter().zip(amounts.iter()) {
+                if amount <= 0 {
+                    return Err(Error::InvalidAmount);
+                }
+                let campaign: Campaign = env
+                    .storage()
+                    .persistent()
+                    .get(&Self::get_campaign_key(campaign_id))
+                    .ok_or(Error::CampaignNotFound)?;
+                Self::check_investment(&env, campaign_id, &campaign, &investor, amount)?;
+            }
+
+            let contract_addr = env.current_contract_address();
+
+            for (campaign_id, amount) in campaign_ids.iter().zip(amounts.iter()) {
+                // Reload so legs on the same campaign see each other's updates
+                let campaign: Campaign = env
+                    .storage()
+                    .persistent()
+                    .get(&Self::get_campaign_key(campaign_id))
+                    .unwrap();
+                Self::check_investment(&env, campaign_id, &campaign, &investor, amount)?;
+
+                let payment_token = token::Client::new(&env, &Self::payment_token(&env, &campaign));
+                if !matches!(payment_token.try_transfer(&investor, &contract_addr, &amount), Ok(Ok(()))) {
+                    return Err(Error::TransferFailed);
+                }
+
+                Self::record_investment(&env, campaign_id, campaign, &investor, amount)?;
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Transfer the investor's unclaimed equity tokens out of the contract
+    /// Investing only records the entitlement, so an under-funded contract cannot block it
+    pub fn claim_tokens(env: Env, campaign_id: u64, investor: Address) -> Result<(), Error> {
+        Self::require_initialized(&env)?;
+        Self::non_reentrant(&env, || {
+            investor.require_auth();
+
+            let campaign: Campaign = env
+                .storage()
+                .persistent()
+                .get(&Self::get_campaign_key(campaign_id))
+                .ok_or(Error::CampaignNotFound)?;
+
+            let investment_key = Self::get_investment_key(campaign_id, &investor);
+            let mut investment: Investment = env
+                .storage()
+                .persistent()
+                .get(&investment_key)
+                .ok_or(Error::NothingToClaim)?;
+
+            let amount = Self::vested_tokens(&env, campaign_id, &investment) - investment.tokens_claimed;
+            if amount <= 0 {
+                return Err(Error::NothingToClaim);
+            }
+
+            // Claims are paid only from tokens reserved in this campaign's own deposit
+            if investment.tokens_received > Self::tokens_sold(&env, campaign_id) {
+                return Err(Error::InsufficientBalance);
+            }
+
+            let equity_token = token::Client::new(&env, &campaign.equity_token_addr);
+            let contract_addr = env.current_contract_address();
+            if !matches!(equity_token.try_transfer(&contract_addr, &investor, &amount), Ok(Ok(()))) {
+                return Err(Error::TransferFailed);
+            }
+
+            // Bonus tokens follow the equity actually claimed, so an investment that is exited
+            // or refunded before claiming never collects them
+            let bonus_amount = Self::bonus_for(&env, campaign_id, investment.tokens_claimed + amount)?
+                - Self::bonus_for(&env, campaign_id, investment.tokens_claimed)?;
+            if bonus_amount > 0 {
+                let bonus = Self::get_bonus_token(env.clone(), campaign_id).unwrap();
+                let bonus_token = token::Client::new(&env, &bonus.token_addr);
+                if !matches!(bonus_token.try_transfer(&contract_addr, &investor, &bonus_amount), Ok(Ok(()))) {
+                    return Err(Error::TransferFailed);
+                }
+            }
+
+            investment.tokens_claimed =
+                investment.tokens_claimed.checked_add(amount).ok_or(Error::Overflow)?;
+            env.storage().persistent().set(&investment_key, &investment);
+
+            TokensClaimedEvent {
+                campaign_id,
+                investor,
+                amount,
+            }
+            .publish(&env);
+
+            Ok(())
+        })
+    }
+
+    /// First phase of a two-phase investment: escrow the XLM without issuing tokens
+    /// Confirm within COMMIT_WINDOW to receive tokens; cancel any time before to get it back
+    pub fn commit_investment(
+        env: Env,
+        campaign_id: u64,
+        investor: Address,
+        amount: i128,
+    ) -> Result<(), Error> {
+        Self::require_initialized(&env)?;
+        Self::non_reentrant(&env, || {
+            Self::require_not_paused(&env)?;
+            investor.require_auth();
+
+            if amount <= 0 {
+                return Err(Error::InvalidAmount);
+            }
+
+            let campaign: Campaign = env
+                .storage()
+                .persistent()
+                .get(&Self::get_campaign_key(campaign_id))
+                .ok_or(Error::CampaignNotFound)?;
+            Self::check_investment(&env, campaign_id, &campaign, &investor, amount)?;
+
+            let commitment_key = Self::get_commitment_key(campaign_id, &investor);
+            if env.storage().persistent().has(&commitment_key) {
+                return Err(Error::CommitmentExists);
+            }
+
+            let payment_token = token::Client::new(&env, &Self::payment_token(&env, &campaign));
+            let contract_addr = env.current_contract_address();
+            if !matches!(payment_token.try_transfer(&investor, &contract_addr, &amount), Ok(Ok(()))) {
+                return Err(Error::TransferFailed);
+            }
+
+            env.storage().persistent().set(
+                &commitment_key,
+                &Commitment {
+                    amount,
+                    committed_at: env.ledger().timestamp(),
+                },
+            );
+
+            InvestmentCommittedEvent {
+                campaign_id,
+                investor,
+                amount,
+            }
+            .publish(&env);
+
+            Ok(())
+        })
+    }
+
+    /// Second phase: exchange a pending commitment for tokens (within COMMIT_WINDOW)
+    pub fn confirm_investment(env: Env, campaign_id: u64, investor: Address) -> Result<(), Error> {
+        Self::require_initialized(&env)?;
+        Self::non_reentrant(&env, || {
+            Self::require_not_paused(&env)?;
+            investor.require_auth();
+
+            let commitment_key = Self::get_commitment_key(campaign_id, &investor);
+            let commitment: Commitment = env
+                .storage()
+                .persistent()
+                .get(&commitment_key)
+                .ok_or(Error::CommitmentNotFound)?;
+
+            if env.ledger().timestamp() > commitment.committed_at + COMMIT_WINDOW {
+                return Err(Error::CommitmentExpired);
+            }
+
+            let campaign = Self::get_campaign(env.clone(), campaign_id);
+            Self::check_investment(&env, campaign_id, &campaign, &investor, commitment.amount)?;
+
+            env.storage().persistent().remove(&commitment_key);
+            Self::record_investment(&env, campaign_id, campaign, &investor, commitment.amount)
+        })
+    }
+
+    /// Return a pending commitment's XLM to the investor, whether cancelled inside the
+    /// window or left unconfirmed past it
+    pub fn cancel_commitment(env: Env, campaign_id: u64, investor: Address) -> Result<(), Error> {
+        Self::require_initialized(&env)?;
+        Self::non_reentrant(&env, || {
+            investor.require_auth();
+
+            let commitment_key = Self::get_commitment_key(campaign_id, &investor);
+            let commitment: Commitment = env
+                .storage()
+                .persistent()
+                .get(&commitment_key)
+                .ok_or(Error::CommitmentNotFound)?;
+            env.storage().persistent().remove(&commitment_key);
+
+            let campaign = Self::get_campaign(env.clone(), campaign_id);
+            let payment_token = token::Client::new(&env, &Self::payment_token(&env, &campaign));
+            payment_token.transfer(&env.current_contract_address(), &investor, &commitment.amount);
+
+            CommitmentCancelledEvent {
+                campaign_id,
+                investor,
+                amount: commitment.amount,
+            }
+            .publish(&env);
+
+            Ok(())
+        })
+    }
+
+    // =============================
+    // 💸 WITHDRAWAL FUNCTIONS
+    // =============================
+    
+    /// Withdraw raised funds (company only, after conditions met)
+    pub fn withdraw_funds(env: Env, campaign_id: u64) -> Result<(), Error> {
+        Self::require_initialized(&env)?;
+        Self::non_reentrant(&env, || {
+            Self::require_not_paused(&env)?;
+
+            let mut campaign: Campaign = env
+                .storage()
+                .persistent()
+                .get(&Self::get_campaign_key(campaign_id))
+                .ok_or(Error::CampaignNotFound)?;
+
+            campaign.company_addr.require_auth();
+
+            if !campaign.is_active {
+                return Err(Error::CampaignInactive);
+            }
+
+            // Check if conditions met for withdrawal
+            if !Self::can_withdraw(&env, &campaign) {
+                return Err(Error::CannotWithdraw);
+            }
+
+            Self::release_funds(&env, campaign_id, &mut campaign);
+            Ok(())
+        })
+    }
+
+    /// Withdraw from every campaign of `company` that meets withdrawal conditions
+    /// Campaigns still in progress are skipped. Returns the total withdrawn.
+    pub fn withdraw_all(env: Env, company: Address) -> i128 {
+        Self::require_initialized(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+        Self::non_reentrant(&env, || {
+            Self::require_not_paused(&env)?;
+            company.require_auth();
+
+            let mut total_withdrawn: i128 = 0;
+            for campaign_id in Self::get_company_campaigns(env.clone(), company.clone()).iter() {
+                let mut campaign = Self::get_campaign(env.clone(), campaign_id);
+                if campaign.company_addr != company || !Self::can_withdraw(&env, &campaign) {
+                    continue;
+                }
+                total_withdrawn = total_withdrawn
+                    .checked_add(Self::release_funds(&env, campaign_id, &mut campaign))
+                    .ok_or(Error::Overflow)?;
+            }
+            Ok(total_withdrawn)
+        })
+        .unwrap_or_else(|e| panic_with_error!(&env, e))
+    }
+
+    /// Pay out a campaign's escrowed funds to the company (or its splits) and close it
+    fn release_funds(env: &Env, campaign_id: u64, campaign: &mut Campaign) -> i128 {
+        let campaign_key = Self::get_campaign_key(campaign_id);
+        let withdraw_amount = campaign.raised_amount
+            - campaign.withdrawn_amount
+            - campaign.refunded_amount
+            - campaign.redeemed_amount;
+
+        let payment_token = token::Client::new(env, &Self::payment_token(env, campaign));
+        let contract_addr = env.current_contract_address();
+
+        // Take the platform fee first
+        let fee = withdraw_amount
+            .checked_mul(Self::effective_fee_bps(env, campaign) as i128)
+            .unwrap_or_else(|| panic_with_error!(env, Error::Overflow))
+            / BPS_DENOMINATOR;
+        if fee > 0 {
+            let fee_collector = Self::get_fee_collector(env.clone());
+            payment_token.transfer(&contract_addr, &fee_collector, &fee);
+            FeeCollectedEvent { campaign_id, fee }.publish(env);
+        }
+        let net_amount = withdraw_amount - fee;
+
+        // Transfer XLM from contract to company
+        let splits = Self::get_withdraw_splits(env.clone(), campaign_id);
+        if splits.is_empty() {
+            payment_token.transfer(&contract_addr, &campaign.company_addr, &net_amount);
+        } else {
+            // Pay each recipient its share, giving the rounding remainder to the last one
+            let mut distributed: i128 = 0;
+            for (i, (recipient, bps)) in splits.iter().enumerate() {
+                let share = if i as u32 == splits.len() - 1 {
+                    net_amount - distributed
+                } else {
+                    net_amount
+                        .checked_mul(bps)
+                        .unwrap_or_else(|| panic_with_error!(env, Error::Overflow))
+                        / BPS_DENOMINATOR
+                };
+                if share > 0 {
+                    payment_token.transfer(&contract_addr, &recipient, &share);
+                }
+                distributed += share;
+            }
+        }
+
+        // Mark campaign as closed
+        campaign.withdrawn_amount = campaign
+            .withdrawn_amount
+            .checked_add(withdraw_amount)
+            .unwrap_or_else(|| panic_with_error!(env, Error::Overflow));
+        campaign.is_active = false;
+        env.storage().persistent().set(&campaign_key, campaign);
+        Self::bump_campaign_ttl(env, campaign_id);
+
+        // Update stats
+        let mut stats: CampaignStats = env.storage().instance().get(&KEY_STATS).unwrap();
+        stats.active_campaigns = stats.active_campaigns.saturating_sub(1);
+        env.storage().instance().set(&KEY_STATS, &stats);
+        Self::record_stats_snapshot(env, &stats);
+
+        // Emit event
+        Self::record_event(env, symbol_short!("withdrawn"), campaign_id, net_amount);
+        WithdrawnEvent {
+            campaign_id,
+            company: campaign.company_addr.clone(),
+            amount: net_amount,
+        }
+        .publish(env);
+        Self::emit_cap_table(env, campaign_id);
+
+        withdraw_amount
+    }
+
+    /// Credit investors bonus tokens for escrow not already paid for by tokens sold at the
+    /// base price, pro rata to amount invested. Afterwards every XLM raised is backed by
+    /// tokens, so the escrow can be released as usual. Rounding stays with the contract.
+    fn convert_residual(env: &Env, campaign_id: u64, campaign: &Campaign) {
+        let rate = Self::get_exchange_rate(env.clone(), campaign_id);
+
+        let mut investments: Vec<Investment> = Vec::new(env);
+        let mut total_invested: i128 = 0;
+        let mut tokens_sold: i128 = 0;
+        for investor in Self::get_investors(env.clone(), campaign_id).iter() {
+            let investment = Self::get_investment(env.clone(), campaign_id, investor);
+            if investment.refunded || investment.amount_invested <= 0 {
+                continue;
+            }
+            total_invested = total_invested
+                .checked_add(investment.amount_invested)
+                .unwrap_or_else(|| panic_with_error!(env, Error::Overflow));
+            tokens_sold = tokens_sold
+                .checked_add(investment.tokens_received)
+                .unwrap_or_else(|| panic_with_error!(env, Error::Overflow));
+            investments.push_back(investment);
+        }
+        if total_invested <= 0 {
+            return;
+        }
+
+        // XLM value of the tokens already sold, in raise-token terms
+        let allocated = tokens_sold
+            .checked_mul(campaign.price_per_token)
+            .and_then(|v| v.checked_mul(RATE_SCALE))
+            .unwrap_or_else(|| panic_with_error!(env, Error::Overflow))
+            / rate;
+        let escrow = campaign.raised_amount
+            - campaign.withdrawn_amount
+            - campaign.refunded_amount
+            - campaign.redeemed_amount;
+        let residual = (escrow - allocated).max(0);
+        let bonus_tokens = (residual / campaign.price_per_token)
+            .checked_mul(rate)
+            .unwrap_or_else(|| panic_with_error!(env, Error::Overflow))
+            / RATE_SCALE;
+        if bonus_tokens <= 0 {
+            return;
+        }
+
+        // Every credited token must come out of this campaign's unsold deposit
+        let available = Self::tokens_remaining(env.clone(), campaign_id);
+        if bonus_tokens > available {
+            panic_with_error!(env, Error::InsufficientBalance);
+        }
+
+        let mut credited: i128 = 0;
+        for mut investment in investments.iter() {
+            let share = bonus_tokens
+                .checked_mul(investment.amount_invested)
+                .unwrap_or_else(|| panic_with_error!(env, Error::Overflow))
+                / total_invested;
+            investment.tokens_received += share;
+            credited += share;
+            env.storage().persistent().set(
+                &Self::get_investment_key(campaign_id, &investment.investor),
+                &investment,
+            );
+        }
+        Self::reserve_tokens(env, campaign_id, credited).unwrap_or_else(|e| panic_with_error!(env, e));
+
+        ResidualConvertedEvent {
+            campaign_id,
+            amount: residual,
+            tokens: credited,
+        }
+        .publish(env);
+    }
+
+    /// Emergency close campaign (admin or company)
+    pub fn close_campaign(env: Env, campaign_id: u64, caller: Address) {
+        Self::require_initialized(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+        Self::non_reentrant(&env, || {
+            caller.require_auth();
+
+            let campaign_key = Self::get_campaign_key(campaign_id);
+            let mut campaign: Campaign = env
+                .storage()
+                .persistent()
+                .get(&campaign_key)
+                .unwrap_or_else(|| panic!("Campaign not found"));
+
+            // Only admin or company can close
+            let admin: Address = env.storage().instance().get(&KEY_ADMIN).unwrap();
+            if caller != admin && caller != campaign.company_addr {
+                panic!("Unauthorized");
+            }
+
+            if campaign.is_active {
+                // Convert the escrow into bonus equity and release it instead of leaving it to withdraw
+                if campaign.auto_convert_residual && Self::can_withdraw(&env, &campaign) {
+                    Self::convert_residual(&env, campaign_id, &campaign);
+                    campaign.closed_at = env.ledger().timestamp();
+                    Self::release_funds(&env, campaign_id, &mut campaign);
+                    Self::record_event(&env, symbol_short!("closed"), campaign_id, 0);
+                    CampaignClosedEvent { campaign_id }.publish(&env);
+                    return Ok(());
+                }
+
+                campaign.is_active = false;
+                campaign.closed_at = env.ledger().timestamp();
+
+                // A failed campaign guarantees investors a window to claim refunds
+                if campaign.raised_amount < campaign.soft_cap {
+                    campaign.refund_deadline =
+                        env.ledger().timestamp() + Self::get_refund_window(env.clone());
+                }
+                env.storage().persistent().set(&campaign_key, &campaign);
+
+                // Update stats
+                let mut stats: CampaignStats = env.storage().instance().get(&KEY_STATS).unwrap();
+                stats.active_campaigns = stats.active_campaigns.saturating_sub(1);
+                env.storage().instance().set(&KEY_STATS, &stats);
+                Self::record_stats_snapshot(&env, &stats);
+
+                if Self::has_succeeded(&env, &campaign) {
+                    Self::emit_cap_table(&env, campaign_id);
+                }
+
+                Self::record_event(&env, symbol_short!("closed"), campaign_id, 0);
+                CampaignClosedEvent { campaign_id }.publish(&env);
+            }
+
+            Ok(())
+        })
+        .unwrap_or_else(|e| panic_with_error!(&env, e));
+    }
+
+    /// Undo a mistaken close within the reactivation window (company only)
+    /// Not possible once funds were withdrawn or refunded, or after the deadline
+    pub fn reactivate_campaign(env: Env, campaign_id: u64) {
+        Self::require_initialized(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+
+        let campaign_key = Self::get_campaign_key(campaign_id);
+        let mut campaign = Self::get_campaign(env.clone(), campaign_id);
+        campaign.company_addr.require_auth();
+
+        if campaign.is_active
+            || campaign.closed_at == 0
+            || campaign.withdrawn_amount > 0
+            || campaign.refunded_amount > 0
+        {
+            panic_with_error!(&env, Error::ReactivationUnavailable);
+        }
+
+        let now = env.ledger().timestamp();
+        if now > campaign.closed_at + Self::get_reactivation_window(env.clone()) {
+            panic_with_error!(&env, Error::ReactivationWindowPassed);
+        }
+
+        if now > campaign.deadline {
+            panic_with_error!(&env, Error::DeadlinePassed);
+        }
+
+        campaign.is_active = true;
+        campaign.closed_at = 0;
+        campaign.refund_deadline = 0;
+        env.storage().persistent().set(&campaign_key, &campaign);
+
+        // Update stats
+        let mut stats: CampaignStats = env.storage().instance().get(&KEY_STATS).unwrap();
+        stats.active_campaigns += 1;
+        env.storage().instance().set(&KEY_STATS, &stats);
+
+        CampaignReactivatedEvent { campaign_id }.publish(&env);
+    }
+
+    /// Push the deadline of an active campaign further out (company only)
+    /// The total duration may not exceed MAX_DURATION_MULTIPLE times the original one
+    pub fn extend_deadline(env: Env, campaign_id: u64, new_deadline: u64) -> Result<(), Error> {
+        Self::require_initialized(&env)?;
+
+        let campaign_key = Self::get_campaign_key(campaign_id);
+        let mut campaign = Self::get_campaign(env.clone(), campaign_id);
+        campaign.company_addr.require_auth();
+
+        if !campaign.is_active {
+            return Err(Error::CampaignInactive);
+        }
+        // Past the deadline investors may already be refunding; no reopening the raise
+        if env.ledger().timestamp() > campaign.deadline || campaign.refunded_amount > 0 {
+            return Err(Error::DeadlineInvalid);
+        }
+
+        let original_duration = campaign.original_deadline - campaign.created_at;
+        let max_deadline = campaign.created_at + original_duration * MAX_DURATION_MULTIPLE;
+        if new_deadline <= campaign.deadline || new_deadline > max_deadline {
+            return Err(Error::DeadlineInvalid);
+        }
+
+        campaign.deadline = new_deadline;
+        env.storage().persistent().set(&campaign_key, &campaign);
+
+        DeadlineExtendedEvent {
+            campaign_id,
+            new_deadline,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    /// Set how long after a close the company may reactivate a campaign (admin only)
+    pub fn set_reactivation_window(env: Env, window_seconds: u64) {
+        Self::require_initialized(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+        let admin: Address = env.storage().instance().get(&KEY_ADMIN).unwrap();
+        admin.require_auth();
+
+        env.storage().instance().set(&KEY_REACTIVATION_WINDOW, &window_seconds);
+    }
+
+    // =============================
+    // ↩️ REFUND FUNCTIONS
+    // =============================
+
+    /// Set how long investors have to claim refunds after a failed campaign is closed (admin only)
+    pub fn set_refund_window(env: Env, window_seconds: u64) {
+        Self::require_initialized(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+        let admin: Address = env.storage().instance().get(&KEY_ADMIN).unwrap();
+        admin.require_auth();
+
+        env.storage().instance().set(&KEY_REFUND_WINDOW, &window_seconds);
+    }
+
+    /// Claim a refund of the investor's XLM from a campaign that failed to reach its target
+    /// The investor's equity tokens are returned to the contract. Claimed tokens no longer
+    /// held cannot be returned, so the refund is cut pro rata to them (`RefundUnavailable`
+    /// once nothing is left to refund); the remainder stays in escrow for the sweep.
+    pub fn claim_refund(env: Env, campaign_id: u64, investor: Address) -> Result<(), Error> {
+        Self::require_initialized(&env)?;
+        Self::non_reentrant(&env, || {
+            if Self::is_refunds_frozen(env.clone()) {
+                return Err(Error::RefundsFrozen);
+            }
+            investor.require_auth();
+
+            let campaign_key = Self::get_campaign_key(campaign_id);
+            let mut campaign: Campaign = env
+                .storage()
+                .persistent()
+                .get(&campaign_key)
+                .ok_or(Error::CampaignNotFound)?;
+
+            if !Self::is_refundable(&env, &campaign) {
+                return Err(Error::RefundUnavailable);
+            }
+
+            if campaign.refund_deadline > 0 && env.ledger().timestamp() > campaign.refund_deadline {
+                return Err(Error::RefundWindowClosed);
+            }
+
+            let investment_key = Self::get_investment_key(campaign_id, &investor);
+            let mut investment: Investment = env
+                .storage()
+                .persistent()
+                .get(&investment_key)
+                .ok_or(Error::RefundUnavailable)?;
+
+            if investment.refunded || investment.amount_invested <= 0 {
+                return Err(Error::RefundUnavailable);
+            }
+
+            let contract_addr = env.current_contract_address();
+            let equity = EquityTokenClient::new(&env, &campaign.equity_token_addr);
+
+            let (refund_amount, returned, kept) = Self::refund_payout(&env, &campaign, &investment)?;
+            if refund_amount <= 0 {
+                return Err(Error::RefundUnavailable);
+            }
+
+            // Bonus tokens paid on the returned equity come back with it
+            let bonus_back = Self::bonus_for(&env, campaign_id, investment.tokens_claimed)?
+                - Self::bonus_for(&env, campaign_id, kept)?;
+            Self::return_bonus(&env, campaign_id, &investor, bonus_back)?;
+
+            // Tokens never claimed or handed back go back into the campaign's deposit
+            Self::reserve_tokens(&env, campaign_id, -(investment.tokens_received - kept))?;
+
+            // Burn the returned equity tokens when this contract is the token's registered
+            // burner, otherwise hand them back to the contract
+            if returned > 0 {
+                if equity.get_burner() == Some(contract_addr.clone()) {
+                    equity.burn_from(&contract_addr, &investor, &returned);
+                    let deposit_key = Self::get_token_deposit_key(campaign_id);
+                    let deposited = Self::get_token_deposit(env.clone(), campaign_id);
+                    env.storage().persistent().set(&deposit_key, &(deposited - returned));
+                } else {
+                    let equity_token = token::Client::new(&env, &campaign.equity_token_addr);
+                    equity_token.transfer(&investor, &contract_addr, &returned);
+                }
+            }
+
+            // Transfer XLM from contract back to investor
+            let payment_token = token::Client::new(&env, &Self::payment_token(&env, &campaign));
+            payment_token.transfer(&contract_addr, &investor, &refund_amount);
+
+            investment.amount_invested = 0;
+            investment.tokens_received = 0;
+            investment.tokens_claimed = 0;
+            investment.refunded = true;
+            env.storage().persistent().set(&investment_key, &investment);
+
+            campaign.refunded_amount =
+                campaign.refunded_amount.checked_add(refund_amount).ok_or(Error::Overflow)?;
+            env.storage().persistent().set(&campaign_key, &campaign);
+
+            // Refunded XLM no longer counts towards the investor's or the platform's totals
+            Self::update_leaderboard(&env, &investor, -refund_amount);
+
+            let mut stats: CampaignStats = env.storage().instance().get(&KEY_STATS).unwrap();
+            stats.total_raised = stats.total_raised.checked_sub(refund_amount).ok_or(Error::Overflow)?;
+            env.storage().instance().set(&KEY_STATS, &stats);
+
+            RefundedEvent {
+                campaign_id,
+                investor,
+                amount: refund_amount,
+            }
+            .publish(&env);
+
+            Ok(())
+        })
+    }
+
+    /// Exit an investment in full while the campaign is below its `min_investors` threshold
+    /// Equity tokens go back to the contract and the XLM is returned to the investor
+    pub fn exit_investment(env: Env, campaign_id: u64, investor: Address) {
+        Self::require_initialized(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+        Self::non_reentrant(&env, || {
+            Self::require_not_paused(&env)?;
+            if Self::is_refunds_frozen(env.clone()) {
+                return Err(Error::RefundsFrozen);
+            }
+            investor.require_auth();
+
+            let campaign_key = Self::get_campaign_key(campaign_id);
+            let mut campaign: Campaign = env
+                .storage()
+                .persistent()
+                .get(&campaign_key)
+                .ok_or(Error::CampaignNotFound)?;
+
+            // Exits draw on this campaign's live escrow, which is gone once it closes or expires
+            if !campaign.is_active {
+                return Err(Error::CampaignInactive);
+            }
+            if env.ledger().timestamp() > campaign.deadline {
+                return Err(Error::DeadlinePassed);
+            }
+
+            if Self::is_locked(env.clone(), campaign_id) {
+                return Err(Error::InvestmentLocked);
+            }
+
+            let investment_key = Self::get_investment_key(campaign_id, &investor);
+            let investment: Investment = env
+                .storage()
+                .persistent()
+                .get(&investment_key)
+                .ok_or(Error::InsufficientBalance)?;
+
+            if investment.refunded || investment.amount_invested <= 0 {
+                return Err(Error::InsufficientBalance);
+            }
+
+            let amount = investment.amount_invested;
+            let contract_addr = env.current_contract_address();
+
+            // Return claimed equity tokens, and the bonus tokens paid on them, to the contract
+            if investment.tokens_claimed > 0 {
+                let bonus_back = Self::bonus_for(&env, campaign_id, investment.tokens_claimed)?;
+                Self::return_bonus(&env, campaign_id, &investor, bonus_back)?;
+                let equity_token = token::Client::new(&env, &campaign.equity_token_addr);
+                equity_token.transfer(&investor, &contract_addr, &investment.tokens_claimed);
+            }
+
+            // Transfer XLM from contract back to investor
+            let payment_token = token::Client::new(&env, &Self::payment_token(&env, &campaign));
+            payment_token.transfer(&contract_addr, &investor, &amount);
+
+            // Every token sold to the investor is back, so release it to the deposit
+            Self::reserve_tokens(&env, campaign_id, -investment.tokens_received)?;
+
+            // Forget the investor entirely, as if they had never invested
+            env.storage().persistent().remove(&investment_key);
+            env.storage()
+                .persistent()
+                .remove(&Self::get_history_key(campaign_id, &investor));
+
+            let investors_key = Self::get_investors_key(campaign_id);
+            let mut investors: Vec<Address> = env.storage().persistent().get(&investors_key).unwrap();
+            if let Some(pos) = investors.first_index_of(&investor) {
+                investors.remove(pos);
+                env.storage().persistent().set(&investors_key, &investors);
+                env.storage()
+                    .persistent()
+                    .set(&Self::get_investor_count_key(campaign_id), &investors.len());
+            }
+
+            campaign.raised_amount = campaign.raised_amount.checked_sub(amount).ok_or(Error::Overflow)?;
+            env.storage().persistent().set(&campaign_key, &campaign);
+
+            Self::update_leaderboard(&env, &investor, -amount);
+
+            let mut stats: CampaignStats = env.storage().instance().get(&KEY_STATS).unwrap();
+            stats.total_raised = stats.total_raised.checked_sub(amount).ok_or(Error::Overflow)?;
+            env.storage().instance().set(&KEY_STATS, &stats);
+
+            InvestmentExitedEvent {
+                campaign_id,
+                investor,
+                amount,
+            }
+            .publish(&env);
+
+            Ok(())
+        })
+        .unwrap_or_else(|e| panic_with_error!(&env, e));
+    }
+
+    /// Sweep XLM left unclaimed after the refund window of a closed campaign (admin only)
+    pub fn sweep_unclaimed(env: Env, campaign_id: u64) {
+        Self::require_initialized(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+        Self::non_reentrant(&env, || {
+            let admin: Address = env.storage().instance().get(&KEY_ADMIN).unwrap();
+            admin.require_auth();
+
+            let campaign_key = Self::get_campaign_key(campaign_id);
+            let mut campaign: Campaign = env
+                .storage()
+                .persistent()
+                .get(&campaign_key)
+                .unwrap_or_else(|| panic!("Campaign not found"));
+
+            // Only once refunds are no longer guaranteed
+            if campaign.refund_deadline == 0 || env.ledger().timestamp() <= campaign.refund_deadline {
+                panic_with_error!(&env, Error::RefundWindowOpen);
+            }
+
+            let sweep_amount = campaign.raised_amount
+                - campaign.withdrawn_amount
+                - campaign.refunded_amount
+                - campaign.redeemed_amount;
+            let unsold = Self::tokens_remaining(env.clone(), campaign_id);
+            if sweep_amount <= 0 && unsold <= 0 {
+                panic_with_error!(&env, Error::InsufficientBalance);
+            }
+
+            let contract_addr = env.current_contract_address();
+            if sweep_amount > 0 {
+                let payment_token = token::Client::new(&env, &Self::payment_token(&env, &campaign));
+                payment_token.transfer(&contract_addr, &admin, &sweep_amount);
+
+                campaign.withdrawn_amount = campaign
+                    .withdrawn_amount
+                    .checked_add(sweep_amount)
+                    .unwrap_or_else(|| panic_with_error!(&env, Error::Overflow));
+                env.storage().persistent().set(&campaign_key, &campaign);
+            }
+
+            // Hand back only this campaign's unsold deposit; tokens investors kept stay sold
+            if unsold > 0 {
+                token::Client::new(&env, &campaign.equity_token_addr).transfer(
+                    &contract_addr,
+                    &campaign.company_addr,
+                    &unsold,
+                );
+                let deposited = Self::get_token_deposit(env.clone(), campaign_id);
+                env.storage()
+                    .persistent()
+                    .set(&Self::get_token_deposit_key(campaign_id), &(deposited - unsold));
+            }
+
+            UnclaimedSweptEvent {
+                campaign_id,
+                amount: sweep_amount.max(0),
+                tokens: unsold,
+            }
+            .publish(&env);
+
+            Ok(())
+        })
+        .unwrap_or_else(|e| panic_with_error!(&env, e));
+    }
+
+    /// Burn `token_amount` of the investor's equity tokens and pay out
+    /// `token_amount * price_per_token` XLM from escrow (redeemable campaigns only)
+    pub fn redeem(env: Env, campaign_id: u64, investor: Address, token_amount: i128) {
+        Self::require_initialized(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+        Self::non_reentrant(&env, || {
+            investor.require_auth();
+
+            let campaign_key = Self::get_campaign_key(campaign_id);
+            let mut campaign = Self::get_campaign(env.clone(), campaign_id);
+
+            if !campaign.redeemable {
+                panic_with_error!(&env, Error::RedemptionDisabled);
+            }
+
+            if token_amount <= 0 {
+                panic_with_error!(&env, Error::InvalidAmount);
+            }
+
+            let investment_key = Self::get_investment_key(campaign_id, &investor);
+            let mut investment: Investment = env
+                .storage()
+                .persistent()
+                .get(&investment_key)
+                .unwrap_or_else(|| panic_with_error!(&env, Error::InsufficientBalance));
+
+            // Only tokens the investor has claimed can be burned
+            if investment.tokens_claimed < token_amount {
+                panic_with_error!(&env, Error::InsufficientBalance);
+            }
+
+            let payout = token_amount
+                .checked_mul(campaign.price_per_token)
+                .unwrap_or_else(|| panic_with_error!(&env, Error::Overflow));
+            let escrowed = campaign.raised_amount
+                - campaign.withdrawn_amount
+                - campaign.refunded_amount
+                - campaign.redeemed_amount;
+            if payout > escrowed {
+                panic_with_error!(&env, Error::InsufficientBalance);
+            }
+
+            // Burn first so a failed burn leaves escrow untouched
+            EquityTokenClient::new(&env, &campaign.equity_token_addr).burn(&investor, &token_amount);
+
+            let payment_token = token::Client::new(&env, &Self::payment_token(&env, &campaign));
+            payment_token.transfer(&env.current_contract_address(), &investor, &payout);
+
+            investment.tokens_received -= token_amount;
+            investment.tokens_claimed -= token_amount;
+            investment.amount_invested = (investment.amount_invested - payout).max(0);
+            env.storage().persistent().set(&investment_key, &investment);
+
+            campaign.redeemed_amount = campaign
+                .redeemed_amount
+                .checked_add(payout)
+                .unwrap_or_else(|| panic_with_error!(&env, Error::Overflow));
+            env.storage().persistent().set(&campaign_key, &campaign);
+
+            RedeemedEvent {
+                campaign_id,
+                investor,
+                token_amount,
+                amount: payout,
+            }
+            .publish(&env);
+
+            Ok(())
+        })
+        .unwrap_or_else(|e| panic_with_error!(&env, e));
+    }
+
+    /// Recompute `raised_amount` from the investors' investment histories (admin only)
+    /// Recovery aid for campaigns left inconsistent by a bug or migration
+    pub fn repair_campaign(env: Env, campaign_id: u64) -> i128 {
+        Self::require_initialized(&env).unwrap_or_else(|e| panic_with_error!(&env, e));
+        let admin: Address = env.storage().instance().get(&KEY_ADMIN).unwrap();
+        admin.require_auth();
+
+        let campaign_key = Self::get_campaign_key(campaign_id);
+        let mut campaign = Self::get_campaign(env.clone(), campaign_id);
+
+        // Sum the principal as invested: refunds and redemptions shrink `amount_invested`
+        // but stay in `raised_amount`, since escrow nets them out separately
+        let mut summed: i128 = 0;
+        for investor in Self::get_investors(env.clone(), campaign_id).iter() {
+            for (_, amount, _) in Self::get_investor_history(env.clone(), campaign_id, investor).iter() {
+                summed = summed
+                    .checked_add(amount)
+                    .unwrap_or_else(|| panic_with_error!(&env, Error::Overflow));
+            }
+        }
+
+        let raised_before = campaign.raised_amount;
+        campaign.raised_amount = summed;
+        env.storage().persistent().set(&campaign_key, &campaign);
+
+        CampaignRepairedEvent {
+            campaign_id,
+            raised_before,
+            raised_after: summed,
+        }
+        .publish(&env);
+
+        summed
+    }
+
+    // =============================
+    // ⏳ STORAGE TTL
+    // =============================
+
+    /// Extend the TTL of a campaign's persistent entries to at least `ledgers` (anyone)
+    /// Covers the campaign, its configuration and every investor's records
+    pub fn extend_ttl(env: Env, campaign_id: u64, ledgers: u32) {
+        let campaign_key = Self::get_campaign_key(campaign_id);
+        if !env.storage().persistent().has(&campaign_key) {
+            panic!("Campaign not found");
+        }
+
+        let ledgers = ledgers.min(env.storage().max_ttl());
+        Self::extend_campaign_ttl(&env, campaign_id, ledgers, ledgers);
+    }
+
+    // =============================
+    // 🔍 QUERY FUNCTIONS
+    // =============================
+    
+    /// Get campaign details
+    pub fn get_campaign(env: Env, campaign_id: u64) -> Campaign {
+        let campaign_key = Self::get_campaign_key(campaign_id);
+        env.storage()
+            .persistent()
+            .get(&campaign_key)
+            .unwrap_or_else(|| panic!("Campaign not found"))
+    }
+
+    /// Get the chain of rounds leading to a campaign, oldest first and ending with the campaign
+    pub fn get_campaign_lineage(env: Env, campaign_id: u64) -> Vec<u64> {
+        let mut lineage: Vec<u64> = Vec::new(&env);
+        let mut current = Some(campaign_id);
+        while let Some(id) = current {
+            lineage.push_front(id);
+            current = Self::get_campaign(env.clone(), id).parent_campaign_id;
+        }
+        lineage
+    }
+
+    /// Get several campaigns in one call, in the order requested
+    /// Ids with no campaign are skipped
+    pub fn get_campaigns(env: Env, ids: Vec<u64>) -> Vec<Campaign> {
+        let mut campaigns: Vec<Campaign> = Vec::new(&env);
+        for campaign_id in ids.iter() {
+            let campaign: Option<Campaign> = env
+                .storage()
+                .persistent()
+                .get(&Self::get_campaign_key(campaign_id));
+            if let Some(campaign) = campaign {
+                campaigns.push_back(campaign);
+            }
+        }
+        campaigns
+    }
+
+    /// Get published campaign ids carrying `tag`, paging the tag index from `start`
+    pub fn get_campaigns_by_tag(env: Env, tag: Symbol, start: u32, limit: u32) -> Vec<u64> {
+        let tagged: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&Self::get_tag_key(&tag))
+            .unwrap_or(Vec::new(&env));
+
+        let mut result: Vec<u64> = Vec::new(&env);
+        if start >= tagged.len() {
+            return result;
+        }
+        for campaign_id in tagged.slice(start..tagged.len().min(start.saturating_add(limit))).iter() {
+            if Self::get_campaign(env.clone(), campaign_id).published {
+                result.push_back(campaign_id);
+            }
+        }
+        result
+    }
+
+    /// Get active, published campaign ids from a page of the all-campaigns registry
+    /// `start`/`limit` page the registry in creation order; inactive ids are filtered out
+    pub fn get_active_campaign_ids(env: Env, start: u32, limit: u32) -> Vec<u64> {
+        let all_campaigns: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&KEY_ALL_CAMPAIGNS)
+            .unwrap_or(Vec::new(&env));
+
+        let mut result: Vec<u64> = Vec::new(&env);
+        if start >= all_campaigns.len() {
+            return result;
+        }
+        let end = all_campaigns.len().min(start.saturating_add(limit));
+        for campaign_id in all_campaigns.slice(start..end).iter() {
+            let campaign = Self::get_campaign(env.clone(), campaign_id);
+            if campaign.published && campaign.is_active {
+                result.push_back(campaign_id);
+            }
+        }
+        result
+    }
+
+    /// Get investment details for an investor
+    pub fn get_investment(env: Env, campaign_id: u64, investor: Address) -> Investment {
+        let investment_key = Self::get_investment_key(campaign_id, &investor);
+        env.storage()
+            .persistent()
+            .get(&investment_key)
+            .unwrap_or(Investment {
+                investor: investor.clone(),
+                amount_invested: 0,
+                tokens_received: 0,
+                tokens_claimed: 0,
+                timestamp: 0,
+                refunded: false,
+            })
+    }
+
+    /// Get a deterministic hash of an investment record for off-chain proofs
+    /// Commits to (campaign_id, investor, amount_invested, tokens_received, timestamp)
+    pub fn get_investment_hash(env: Env, campaign_id: u64, investor: Address) -> BytesN<32> {
+        let investment = Self::get_investment(env.clone(), campaign_id, investor.clone());
+        let preimage = (
+            campaign_id,
+            investor,
+            investment.amount_invested,
+            investment.tokens_received,
+            investment.timestamp,
+        )
+            .to_xdr(&env);
+        env.crypto().sha256(&preimage).into()
+    }
+
+    /// Re-issue an investment receipt after the investment changed (e.g. a redemption)
+    /// Earlier receipts stop validating once the record they commit to has changed
+    pub fn reissue_receipt(env: Env, campaign_id: u64, investor: Address) -> BytesN<32> {
+        if !Self::has_invested(env.clone(), campaign_id, investor.clone()) {
+            panic_with_error!(&env, Error::InsufficientBalance);
+        }
+
+        let receipt = Self::get_investment_hash(env.clone(), campaign_id, investor.clone());
+        ReceiptReissuedEvent {
+            campaign_id,
+            investor,
+            receipt: receipt.clone(),
+        }
+        .publish(&env);
+        receipt
+    }
+
+    /// Whether `receipt` matches the investment record as it stands now
+    pub fn is_receipt_valid(env: Env, campaign_id: u64, investor: Address, receipt: BytesN<32>) -> bool {
+        Self::get_investment_hash(env, campaign_id, investor) == receipt
+    }
+
+    /// Get all investors for a campaign
+    pub fn get_investors(env: Env, campaign_id: u64) -> Vec<Address> {
+        let investors_key = Self::get_investors_key(campaign_id);
+        env.storage()
+            .persistent()
+            .get(&investors_key)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Get up to `limit` investors starting at position `start` (empty past the end)
+    pub fn get_investors_paged(env: Env, campaign_id: u64, start: u32, limit: u32) -> Vec<Address> {
+        let investors = Self::get_investors(env.clone(), campaign_id);
+        if start >= investors.len() {
+            return Vec::new(&env);
+        }
+        let end = start.saturating_add(limit).min(investors.len());
+        investors.slice(start..end)
+    }
+
+    /// Get investor count
+    pub fn get_investor_count(env: Env, campaign_id: u64) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&Self::get_investor_count_key(campaign_id))
+            .unwrap_or(0)
+    }
+
+    /// Get the ids of all campaigns created by a company
+    pub fn get_company_campaigns(env: Env, company: Address) -> Vec<u64> {
+        env.storage()
+            .persistent()
+            .get(&Self::get_company_campaigns_key(&company))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Get aggregated figures across all of a company's campaigns
+    pub fn get_company_dashboard(env: Env, company: Address) -> CompanyDashboard {
+        let mut dashboard = CompanyDashboard {
+            total_campaigns: 0,
+            active: 0,
+            succeeded: 0,
+            total_raised: 0,
+            total_investors: 0,
+        };
+
+        for campaign_id in Self::get_company_campaigns(env.clone(), company).iter() {
+            let campaign = Self::get_campaign(env.clone(), campaign_id);
+            dashboard.total_campaigns += 1;
+            if campaign.is_active {
+                dashboard.active += 1;
+            }
+            if Self::has_succeeded(&env, &campaign) {
+                dashboard.succeeded += 1;
+            }
+            dashboard.total_raised = dashboard
+                .total_raised
+                .checked_add(campaign.raised_amount)
+                .unwrap_or_else(|| panic_with_error!(&env, Error::Overflow));
+            dashboard.total_investors = dashboard
+                .total_investors
+                .checked_add(Self::get_investor_count(env.clone(), campaign_id))
+                .unwrap_or_else(|| panic_with_error!(&env, Error::Overflow));
+        }
+        dashboard
+    }
+
+    /// Get the number of invest transactions for a campaign (top-ups included)
+    pub fn get_transaction_count(env: Env, campaign_id: u64) -> u64 {
+        env.storage()
+            .persistent()
+            .get(&Self::get_tx_count_key(campaign_id))
+            .unwrap_or(0)
+    }
+
+    /// Get global stats
+    pub fn get_stats(env: Env) -> CampaignStats {
+        env.storage()
+            .instance()
+            .get(&KEY_STATS)
+            .unwrap_or(CampaignStats {
+                total_campaigns: 0,
+                active_campaigns: 0,
+                total_raised: 0,
+            })
+    }
+
+    /// Get per-campaign stats (all zeros until the first investment)
+    pub fn get_campaign_stats(env: Env, campaign_id: u64) -> CampaignDetailStats {
+        let campaign = Self::get_campaign(env.clone(), campaign_id);
+        let investor_count = Self::get_investor_count(env.clone(), campaign_id);
+        let avg_investment = if investor_count > 0 {
+            campaign.raised_amount / investor_count as i128
+        } else {
+            0
+        };
+
+        CampaignDetailStats {
+            investor_count,
+            raised_amount: campaign.raised_amount,
+            avg_investment,
+            largest_investment: env
+                .storage()
+                .persistent()
+                .get(&Self::get_largest_investment_key(campaign_id))
+                .unwrap_or(0),
+        }
+    }
+
+    /// Get the platform stats snapshots taken at each withdrawal and close, oldest first
+    pub fn get_stats_history(env: Env) -> Vec<CampaignStats> {
+        env.storage()
+            .instance()
+            .get(&KEY_STATS_HISTORY)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Get the investor's unrealized gain or loss: their tokens valued at `market_price`
+    /// (the campaign price when None) minus what they paid for them
+    pub fn get_pnl(env: Env, campaign_id: u64, investor: Address, market_price: Option<i128>) -> i128 {
+        let investment = Self::get_investment(env.clone(), campaign_id, investor);
+        let price = market_price
+            .unwrap_or_else(|| Self::get_campaign(env.clone(), campaign_id).price_per_token);
+        investment
+            .tokens_received
+            .checked_mul(price)
+            .and_then(|value| value.checked_sub(investment.amount_invested))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::Overflow))
+    }
+
+    /// Get the investor's live balance of the campaign's equity token
+    pub fn get_my_token_balance(env: Env, campaign_id: u64, investor: Address) -> i128 {
+        let campaign = Self::get_campaign(env.clone(), campaign_id);
+        EquityTokenClient::new(&env, &campaign.equity_token_addr).balance_of(&investor)
+    }
+
+    /// Get each individual investment as (timestamp, amount, tokens), oldest first
+    pub fn get_investor_history(
+        env: Env,
+        campaign_id: u64,
+        investor: Address,
+    ) -> Vec<(u64, i128, i128)> {
+        env.storage()
+            .persistent()
+            .get(&Self::get_history_key(campaign_id, &investor))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Get the top investors by total XLM invested across all campaigns, highest first
+    pub fn get_top_investors(env: Env, limit: u32) -> Vec<(Address, i128)> {
+        let leaderboard: Vec<(Address, i128)> = env
+            .storage()
+            .instance()
+            .get(&KEY_LEADERBOARD)
+            .unwrap_or(Vec::new(&env));
+        leaderboard.slice(0..limit.min(leaderboard.len()))
+    }
+
+    /// Get a campaign's top investors as (investor, amount_invested, tokens_received),
+    /// largest investment first
+    pub fn get_top_campaign_investors(
+        env: Env,
+        campaign_id: u64,
+        limit: u32,
+    ) -> Vec<(Address, i128, i128)> {
+        let mut result: Vec<(Address, i128, i128)> = Vec::new(&env);
+        for investment in
+            Self::ranked_investments(&env, campaign_id, limit, |inv| inv.amount_invested).iter()
+        {
+            result.push_back((
+                investment.investor,
+                investment.amount_invested,
+                investment.tokens_received,
+            ));
+        }
+        result
+    }
+
+    /// Get how much more an investor may invest under the campaign's caps
+    /// Returns i128::MAX when the campaign has no per-investor cap
+    pub fn get_remaining_allowance(env: Env, campaign_id: u64, investor: Address) -> i128 {
+        let campaign = Self::get_campaign(env.clone(), campaign_id);
+        Self::remaining_allowance(&env, campaign_id, &campaign, &investor)
+    }
+
+    /// Get the equity tokens the contract still has available for a campaign's investors
+    /// This is the campaign's own deposit less the tokens already sold in it, claimed or not.
+    /// Returns 0 for an unknown campaign.
+    pub fn tokens_remaining(env: Env, campaign_id: u64) -> i128 {
+        if !env.storage().persistent().has(&Self::get_campaign_key(campaign_id)) {
+            return 0;
+        }
+
+        Self::get_token_deposit(env.clone(), campaign_id)
+            .saturating_sub(Self::tokens_sold(&env, campaign_id))
+            .max(0)
+    }
+
+    /// Get how much more the campaign can raise before reaching its target
+    /// Returns 0 once the target is met or for an unknown campaign
+    pub fn max_investable(env: Env, campaign_id: u64) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&Self::get_campaign_key(campaign_id))
+            .map(|campaign: Campaign| (campaign.target_amount - campaign.raised_amount).max(0))
+            .unwrap_or(0)
+    }
+
+    /// Get the per-investor cap currently in force (0 = no limit)
+    pub fn get_investor_cap(env: Env, campaign_id: u64) -> i128 {
+        let campaign = Self::get_campaign(env.clone(), campaign_id);
+        Self::effective_max_investment(&env, campaign_id, &campaign)
+    }
+
+    /// Get an investor's pending two-phase commitment, if any
+    pub fn get_commitment(env: Env, campaign_id: u64, investor: Address) -> Option<Commitment> {
+        env.storage()
+            .persistent()
+            .get(&Self::get_commitment_key(campaign_id, &investor))
+    }
+
+    /// Get the progressive cap configuration for a campaign, if any
+    pub fn get_progressive_cap(env: Env, campaign_id: u64) -> Option<ProgressiveCap> {
+        env.storage().persistent().get(&Self::get_progressive_cap_key(campaign_id))
+    }
+
+    /// Get the campaign's vesting schedule, if any
+    pub fn get_vesting_schedule(env: Env, campaign_id: u64) -> Option<VestingSchedule> {
+        env.storage().persistent().get(&Self::get_vesting_key(campaign_id))
+    }
+
+    /// Get how many of the investor's tokens have vested so far (claimed ones included)
+    pub fn vested_amount(env: Env, campaign_id: u64, investor: Address) -> i128 {
+        let investment = Self::get_investment(env.clone(), campaign_id, investor);
+        Self::vested_tokens(&env, campaign_id, &investment)
+    }
+
+    /// Get the campaign's equity token metadata (name, symbol, equity percent, ...)
+    pub fn get_campaign_token_info(env: Env, campaign_id: u64) -> CompanyInfo {
+        let campaign = Self::get_campaign(env.clone(), campaign_id);
+        EquityTokenClient::new(&env, &campaign.equity_token_addr).get_company_info()
+    }
+
+    /// Check whether investments in a campaign are binding
+    /// Always true unless the campaign is still below its `min_investors` threshold
+    pub fn is_locked(env: Env, campaign_id: u64) -> bool {
+        let campaign = Self::get_campaign(env, campaign_id);
+        campaign.min_investors == 0 || campaign.locked
+    }
+
+    /// Check if investor has invested in campaign
+    pub fn has_invested(env: Env, campaign_id: u64, investor: Address) -> bool {
+        let investment_key = Self::get_investment_key(campaign_id, &investor);
+        env.storage().persistent().has(&investment_key)
+    }
+
+    /// Check for investment records whose campaign entry no longer exists (recovery aid)
+    pub fn has_orphaned_investments(env: Env, campaign_id: u64) -> bool {
+        let campaign_exists = env
+            .storage()
+            .persistent()
+            .has(&Self::get_campaign_key(campaign_id));
+        !campaign_exists && !Self::get_investors(env, campaign_id).is_empty()
+    }
+
+    /// Reconcile recorded token issuance against the equity token's balances: the tokens
+    /// investors have claimed must not exceed what actually left the contract since
+    /// activation. Returns false on a mismatch such as double issuance.
+    pub fn verify_token_distribution(env: Env, campaign_id: u64) -> bool {
+        let campaign = Self::get_campaign(env.clone(), campaign_id);
+        let deposited: i128 = env
+            .storage()
+            .persistent()
+            .get(&Self::get_token_deposit_key(campaign_id))
+            .unwrap_or(0);
+        let current = EquityTokenClient::new(&env, &campaign.equity_token_addr)
+            .balance_of(&env.current_contract_address());
+        let distributed = deposited - current;
+
+        let mut recorded: i128 = 0;
+        for investor in Self::get_investors(env.clone(), campaign_id).iter() {
+            recorded = recorded
+                .checked_add(Self::get_investment(env.clone(), campaign_id, investor).tokens_claimed)
+                .unwrap_or_else(|| panic_with_error!(&env, Error::Overflow));
+        }
+
+        recorded <= distributed
+    }
+
+    /// Get (funds_progress_bps, tokens_distributed_bps): the share of the target raised and
+    /// the share of the tokens deposited at activation that has left the contract.
+    /// A wide gap between the two points to a mispriced or under-funded campaign.
+    pub fn get_utilization(env: Env, campaign_id: u64) -> (i128, i128) {
+        let campaign = Self::get_campaign(env.clone(), campaign_id);
+        let funds_progress_bps = campaign
+            .raised_amount
+            .checked_mul(BPS_DENOMINATOR)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::Overflow))
+            / campaign.target_amount;
+
+        let deposited: i128 = env
+            .storage()
+            .persistent()
+            .get(&Self::get_token_deposit_key(campaign_id))
+            .unwrap_or(0);
+        if deposited <= 0 {
+            return (funds_progress_bps, 0);
+        }
+        let current = EquityTokenClient::new(&env, &campaign.equity_token_addr)
+            .balance_of(&env.current_contract_address());
+        let tokens_distributed_bps = (deposited - current)
+            .checked_mul(BPS_DENOMINATOR)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::Overflow))
+            / deposited;
+
+        (funds_progress_bps, tokens_distributed_bps)
+    }
+
+    /// Get how far the equity token's own `token_price` sits from the campaign's
+    /// `price_per_token`, in bps of the campaign price (positive = token priced higher)
+    pub fn get_price_discrepancy(env: Env, campaign_id: u64) -> i128 {
+        let campaign = Self::get_campaign(env.clone(), campaign_id);
+        let token_price = EquityTokenClient::new(&env, &campaign.equity_token_addr)
+            .get_company_info()
+            .token_price;
+
+        token_price
+            .checked_sub(campaign.price_per_token)
+            .and_then(|gap| gap.checked_mul(BPS_DENOMINATOR))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::Overflow))
+            / campaign.price_per_token
+    }
+
+    /// Get campaign progress (percentage)
+    pub fn get_campaign_progress(env: Env, campaign_id: u64) -> i128 {
+        let campaign = Self::get_campaign(env, campaign_id);
+        if campaign.target_amount == 0 {
+            return 0;
+        }
+        (campaign.raised_amount * 100) / campaign.target_amount
+    }
+
+    /// Get every numeric campaign field plus derived values in one packed read.
+    ///
+    /// Field order (all values as i128):
+    ///  0 target_amount, 1 price_per_token, 2 raised_amount, 3 withdrawn_amount,
+    ///  4 refunded_amount, 5 redeemed_amount, 6 min_investment, 7 max_investment,
+    ///  8 max_tokens_per_investor, 9 max_investments_per_investor, 10 deadline,
+    ///  11 created_at, 12 activated_at, 13 closed_at, 14 refund_deadline,
+    ///  15 progress (%), 16 remaining to target, 17 status code
+    ///  (0 draft, 1 pending, 2 open, 3 succeeded, 4 failed), 18 investor count,
+    ///  19 transaction count, 20 soft_cap
+    pub fn describe_campaign(env: Env, campaign_id: u64) -> Vec<i128> {
+        let campaign = Self::get_campaign(env.clone(), campaign_id);
+
+        let status = if !campaign.published {
+            STATUS_DRAFT
+        } else if campaign.raised_amount >= campaign.target_amount {
+            STATUS_SUCCEEDED
+        } else if campaign.is_active && env.ledger().timestamp() <= campaign.deadline {
+            STATUS_OPEN
+        } else if campaign.activated_at > 0 && campaign.raised_amount >= campaign.soft_cap {
+            STATUS_SUCCEEDED
+        } else if campaign.activated_at == 0 && !campaign.is_active {
+            STATUS_PENDING
+        } else {
+            STATUS_FAILED
+        };
+
+        let mut packed: Vec<i128> = Vec::new(&env);
+        packed.push_back(campaign.target_amount);
+        packed.push_back(campaign.price_per_token);
+        packed.push_back(campaign.raised_amount);
+        packed.push_back(campaign.withdrawn_amount);
+        packed.push_back(campaign.refunded_amount);
+        packed.push_back(campaign.redeemed_amount);
+        packed.push_back(campaign.min_investment);
+        packed.push_back(campaign.max_investment);
+        packed.push_back(campaign.max_tokens_per_investor);
+        packed.push_back(campaign.max_investments_per_investor as i128);
+        packed.push_back(campaign.deadline as i128);
+        packed.push_back(campaign.created_at as i128);
+        packed.push_back(campaign.activated_at as i128);
+        packed.push_back(campaign.closed_at as i128);
+        packed.push_back(campaign.refund_deadline as i128);
+        packed.push_back(Self::get_campaign_progress(env.clone(), campaign_id));
+        packed.push_back((campaign.target_amount - campaign.raised_amount).max(0));
+        packed.push_back(status);
+        packed.push_back(Self::get_investor_count(env.clone(), campaign_id) as i128);
+        packed.push_back(Self::get_transaction_count(env, campaign_id) as i128);
+        packed.push_back(campaign.soft_cap);
+        packed
+    }
+
+    /// Get fill rate (bps of target raised per day since creation)
+    pub fn get_fill_rate(env: Env, campaign_id: u64) -> i128 {
+        let campaign = Self::get_campaign(env.clone(), campaign_id);
+        let elapsed = env.ledger().timestamp().saturating_sub(campaign.created_at);
+        if elapsed == 0 || campaign.target_amount == 0 {
+            return 0;
+        }
+        let per_day = campaign
+            .raised_amount
+            .checked_mul(BPS_DENOMINATOR * SECONDS_PER_DAY as i128)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::Overflow));
+        let target_time = campaign
+            .target_amount
+            .checked_mul(elapsed as i128)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::Overflow));
+        per_day / target_time
+    }
+
+    /// Project days until the target is reached at the current fill rate
+    /// Returns 0 once the target is reached and u64::MAX when nothing has been raised yet
+    pub fn project_days_to_target(env: Env, campaign_id: u64) -> u64 {
+        let campaign = Self::get_campaign(env.clone(), campaign_id);
+        if campaign.raised_amount >= campaign.target_amount {
+            return 0;
+        }
+
+        let fill_rate = Self::get_fill_rate(env, campaign_id);
+        if fill_rate <= 0 {
+            return u64::MAX;
+        }
+
+        let remaining_bps = ((campaign.target_amount - campaign.raised_amount) * BPS_DENOMINATOR)
+            / campaign.target_amount;
+        ((remaining_bps + fill_rate - 1) / fill_rate) as u64
+    }
+
+    /// Get the amount the company could withdraw right now, net of the platform fee
+    /// (0 if conditions not met)
+    pub fn get_withdrawable(env: Env, campaign_id: u64) -> i128 {
+        let campaign = Self::get_campaign(env.clone(), campaign_id);
+        if !Self::can_withdraw(&env, &campaign) {
+            return 0;
+        }
+        let withdraw_amount = campaign.raised_amount
+            - campaign.withdrawn_amount
+            - campaign.refunded_amount
+            - campaign.redeemed_amount;
+        let pending_fee = withdraw_amount
+            .checked_mul(Self::effective_fee_bps(&env, &campaign) as i128)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::Overflow))
+            / BPS_DENOMINATOR;
+        withdraw_amount - pending_fee
+    }
+
+    /// Get the contract's outstanding liabilities across all campaigns as
+    /// (refundable_total, withdrawable_total, committed_tokens_value): XLM still owed to
+    /// investors of failed campaigns, XLM companies can withdraw (net of fees), and the
+    /// XLM value of equity tokens investors have not yet claimed
+    pub fn get_obligations(env: Env) -> (i128, i128, i128) {
+        let all_campaigns: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&KEY_ALL_CAMPAIGNS)
+            .unwrap_or(Vec::new(&env));
+
+        let mut refundable_total: i128 = 0;
+        let mut withdrawable_total: i128 = 0;
+        let mut committed_tokens_value: i128 = 0;
+
+        for campaign_id in all_campaigns.iter() {
+            let campaign = Self::get_campaign(env.clone(), campaign_id);
+
+            if Self::is_refundable(&env, &campaign) {
+                for investor in Self::get_investors(env.clone(), campaign_id).iter() {
+                    let investment = Self::get_investment(env.clone(), campaign_id, investor);
+                    refundable_total = Self::refund_payout(&env, &campaign, &investment)
+                        .ok()
+                        .and_then(|(refund, _, _)| refundable_total.checked_add(refund))
+                        .unwrap_or_else(|| panic_with_error!(&env, Error::Overflow));
+                }
+            }
+            withdrawable_total = withdrawable_total
+                .checked_add(Self::get_withdrawable(env.clone(), campaign_id))
+                .unwrap_or_else(|| panic_with_error!(&env, Error::Overflow));
+
+            for investor in Self::get_investors(env.clone(), campaign_id).iter() {
+                let investment = Self::get_investment(env.clone(), campaign_id, investor);
+                let unclaimed = investment.tokens_received - investment.tokens_claimed;
+                committed_tokens_value = unclaimed
+                    .checked_mul(campaign.price_per_token)
+                    .and_then(|value| committed_tokens_value.checked_add(value))
+                    .unwrap_or_else(|| panic_with_error!(&env, Error::Overflow));
+            }
+        }
+
+        (refundable_total, withdrawable_total, committed_tokens_value)
+    }
+
+    /// Get up to `count` of the most recent events, oldest first
+    pub fn get_recent_events(env: Env, count: u32) -> Vec<EventRecord> {
+        let log: Vec<EventRecord> = env
+            .storage()
+            .instance()
+            .get(&KEY_EVENT_LOG)
+            .unwrap_or(Vec::new(&env));
+        let start = log.len().saturating_sub(count);
+        log.slice(start..)
+    }
+
+    /// Get the allowance `owner` has granted this contract on `token` (for pull-based flows)
+    pub fn get_spender_allowance(env: Env, token: Address, owner: Address) -> i128 {
+        let token_client = token::Client::new(&env, &token);
+        token_client.allowance(&owner, &env.current_contract_address())
+    }
+
+    /// Get (already_refunded, refundable_remaining) for an investor
+    /// The remaining amount is 0 unless a refund could be claimed right now
+    pub fn get_refund_status(env: Env, campaign_id: u64, investor: Address) -> (bool, i128) {
+        let campaign = Self::get_campaign(env.clone(), campaign_id);
+        let investment = Self::get_investment(env.clone(), campaign_id, investor);
+        if investment.refunded {
+            return (true, 0);
+        }
+
+        let window_open =
+            campaign.refund_deadline == 0 || env.ledger().timestamp() <= campaign.refund_deadline;
+        if Self::is_refundable(&env, &campaign) && window_open {
+            let (refund, _, _) = Self::refund_payout(&env, &campaign, &investment)
+                .unwrap_or_else(|e| panic_with_error!(&env, e));
+            (false, refund)
+        } else {
+            (false, 0)
+        }
+    }
+
+    /// Get the largest raise achieved by any campaign as (campaign_id, raised_amount)
+    /// Returns (0, 0) before the first investment
+    pub fn get_record_raise(env: Env) -> (u64, i128) {
+        env.storage().instance().get(&KEY_RECORD_RAISE).unwrap_or((0, 0))
+    }
+
+    /// Get the global platform fee taken from withdrawals (bps)
+    pub fn get_fee(env: Env) -> u32 {
+        env.storage().instance().get(&KEY_FEE_BPS).unwrap_or(0)
+    }
+
+    /// Get the address platform fees are paid to (the admin unless set)
+    pub fn get_fee_collector(env: Env) -> Address {
+        env.storage()
+            .instance()
+            .get(&KEY_FEE_COLLECTOR)
+            .unwrap_or_else(|| env.storage().instance().get(&KEY_ADMIN).unwrap())
+    }
+
+    /// Get the cap on equity (bps) a single campaign may sell, if configured
+    pub fn get_max_sellable_bps(env: Env) -> Option<i128> {
+        env.storage().instance().get(&KEY_MAX_SELLABLE)
+    }
+
+    /// Get the refund window applied when a failed campaign is closed (seconds)
+    pub fn get_refund_window(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&KEY_REFUND_WINDOW)
+            .unwrap_or(DEFAULT_REFUND_WINDOW)
+    }
+
+    /// Get how long after a close a campaign may be reactivated (seconds)
+    pub fn get_reactivation_window(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&KEY_REACTIVATION_WINDOW)
+            .unwrap_or(DEFAULT_REACTIVATION_WINDOW)
+    }
+
+    /// Get the bonus token configuration for a campaign, if any
+    pub fn get_bonus_token(env: Env, campaign_id: u64) -> Option<BonusToken> {
+        env.storage().persistent().get(&Self::get_bonus_key(campaign_id))
+    }
+
+    /// Get the withdrawal split recipients and their bps shares
+    pub fn get_withdraw_splits(env: Env, campaign_id: u64) -> Vec<(Address, i128)> {
+        env.storage()
+            .persistent()
+            .get(&Self::get_splits_key(campaign_id))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Get the price tiers configured for a campaign
+    pub fn get_price_tiers(env: Env, campaign_id: u64) -> Vec<PriceTier> {
+        env.storage()
+            .persistent()
+            .get(&Self::get_tiers_key(campaign_id))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Get the price per token a new investment would currently start at
+    pub fn get_current_price(env: Env, campaign_id: u64) -> i128 {
+        let campaign = Self::get_campaign(env.clone(), campaign_id);
+        Self::current_price(&env, campaign_id, &campaign)
+    }
+
+    /// Get how many tokens 1 XLM buys at the campaign's base price
+    pub fn get_tokens_per_xlm(env: Env, campaign_id: u64) -> i128 {
+        let campaign = Self::get_campaign(env, campaign_id);
+        if campaign.price_per_token <= 0 {
+            return 0;
+        }
+        STROOPS_PER_XLM / campaign.price_per_token
+    }
+
+    /// Get the raise-token to XLM exchange rate applied to issuance (10,000,000 = 1:1)
+    pub fn get_exchange_rate(env: Env, campaign_id: u64) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&Self::get_exchange_rate_key(campaign_id))
+            .unwrap_or(RATE_SCALE)
+    }
+
+    /// Get the price decay configuration for a campaign, if any
+    pub fn get_price_decay(env: Env, campaign_id: u64) -> Option<PriceDecay> {
+        env.storage().persistent().get(&Self::get_decay_key(campaign_id))
+    }
+
+    /// Get how much (bps) existing investors' share of the campaign's tokens would shrink
+    /// if `additional_amount` more XLM were raised at current prices
+    /// e.g. 2,000 means an investor holding 50% would hold 40% afterwards
+    pub fn get_dilution_after(env: Env, campaign_id: u64, additional_amount: i128) -> i128 {
+        let campaign = Self::get_campaign(env.clone(), campaign_id);
+        if additional_amount <= 0 {
+            return 0;
+        }
+
+        let mut tokens_sold: i128 = 0;
+        for investor in Self::get_investors(env.clone(), campaign_id).iter() {
+            tokens_sold = tokens_sold
+                .checked_add(Self::get_investment(env.clone(), campaign_id, investor).tokens_received)
+                .unwrap_or_else(|| panic_with_error!(&env, Error::Overflow));
+        }
+
+        let new_tokens = Self::calculate_tokens(&env, campaign_id, &campaign, additional_amount)
+            .checked_mul(Self::get_exchange_rate(env.clone(), campaign_id))
+            .unwrap_or_else(|| panic_with_error!(&env, Error::Overflow))
+            / RATE_SCALE;
+        let total_tokens = tokens_sold
+            .checked_add(new_tokens)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::Overflow));
+        if total_tokens == 0 {
+            return 0;
+        }
+        new_tokens
+            .checked_mul(BPS_DENOMINATOR)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::Overflow))
+            / total_tokens
+    }
+
+    /// Get the price impact (bps) of investing `amount`: blended price vs current tier price
+    pub fn get_price_impact(env: Env, campaign_id: u64, amount: i128) -> i128 {
+        let campaign = Self::get_campaign(env.clone(), campaign_id);
+        let tokens = Self::calculate_tokens(&env, campaign_id, &campaign, amount);
+        if amount <= 0 || tokens <= 0 {
+            return 0;
+        }
+
+        let current_price = Self::current_price(&env, campaign_id, &campaign);
+        let blended_price_bps = amount
+            .checked_mul(BPS_DENOMINATOR)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::Overflow))
+            / tokens;
+        let current_price_bps = current_price
+            .checked_mul(BPS_DENOMINATOR)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::Overflow));
+        (blended_price_bps - current_price_bps) / current_price
+    }
+
+    // =============================
+    // 🔧 HELPER FUNCTIONS
+    // =============================
+    
+    fn record_event(env: &Env, kind: Symbol, campaign_id: u64, amount: i128) {
+        let mut log: Vec<EventRecord> = env
+            .storage()
+            .instance()
+            .get(&KEY_EVENT_LOG)
+            .unwrap_or(Vec::new(env));
+
+        // Drop the oldest entry once the ring buffer is full
+        if log.len() >= MAX_EVENT_LOG {
+            log.pop_front();
+        }
+        log.push_back(EventRecord {
+            kind,
+            campaign_id,
+            amount,
+            timestamp: env.ledger().timestamp(),
+        });
+        env.storage().instance().set(&KEY_EVENT_LOG, &log);
+    }
+
+    fn record_stats_snapshot(env: &Env, stats: &CampaignStats) {
+        let mut history: Vec<CampaignStats> = env
+            .storage()
+            .instance()
+            .get(&KEY_STATS_HISTORY)
+            .unwrap_or(Vec::new(env));
+
+        // Drop the oldest snapshot once the ring buffer is full
+        if history.len() >= MAX_STATS_HISTORY {
+            history.pop_front();
+        }
+        history.push_back(stats.clone());
+        env.storage().instance().set(&KEY_STATS_HISTORY, &history);
+    }
+
+    fn require_draft(env: &Env, campaign: &Campaign) {
+        if campaign.published {
+            panic_with_error!(env, Error::CampaignAlreadyPublished);
+        }
+    }
+
+    fn current_price(env: &Env, campaign_id: u64, campaign: &Campaign) -> i128 {
+        match Self::get_price_decay(env.clone(), campaign_id) {
+            Some(decay) => Self::decayed_price(env, campaign, &decay),
+            None => Self::current_tier_price(env, campaign_id, campaign),
+        }
+    }
+
+    /// Linear interpolation from start price (at creation) to floor price (at deadline)
+    fn decayed_price(env: &Env, campaign: &Campaign, decay: &PriceDecay) -> i128 {
+        let duration = campaign.deadline.saturating_sub(campaign.created_at);
+        let elapsed = env
+            .ledger()
+            .timestamp()
+            .saturating_sub(campaign.created_at)
+            .min(duration);
+        if duration == 0 {
+            return decay.floor_price;
+        }
+        decay.start_price
+            - ((decay.start_price - decay.floor_price) * elapsed as i128) / duration as i128
+    }
+
+    fn current_tier_price(env: &Env, campaign_id: u64, campaign: &Campaign) -> i128 {
+        let tiers = Self::get_price_tiers(env.clone(), campaign_id);
+        for tier in tiers.iter() {
+            if campaign.raised_amount < tier.up_to {
+                return tier.price_per_token;
+            }
+        }
+        campaign.price_per_token
+    }
+
+    /// Equity tokens sold in this campaign so far, claimed or not
+    fn tokens_sold(env: &Env, campaign_id: u64) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&Self::get_tokens_sold_key(campaign_id))
+            .unwrap_or(0)
+    }
+
+    /// Reserve `tokens` of the campaign's own deposit for investors (negative releases them)
+    /// Every sale is checked here, so no campaign can promise more tokens than it deposited
+    fn reserve_tokens(env: &Env, campaign_id: u64, tokens: i128) -> Result<(), Error> {
+        let sold = Self::tokens_sold(env, campaign_id)
+            .checked_add(tokens)
+            .ok_or(Error::Overflow)?;
+        if sold > Self::get_token_deposit(env.clone(), campaign_id) {
+            return Err(Error::InsufficientBalance);
+        }
+        env.storage()
+            .persistent()
+            .set(&Self::get_tokens_sold_key(campaign_id), &sold.max(0));
+        Ok(())
+    }
+
+    /// Equity tokens needed to sell the whole target, at the decay floor when prices decay
+    fn tokens_required(env: &Env, campaign_id: u64, campaign: &Campaign) -> i128 {
+        let tokens = match Self::get_price_decay(env.clone(), campaign_id) {
+            Some(decay) => campaign.target_amount / decay.floor_price,
+            None => {
+                let mut unsold = campaign.clone();
+                unsold.raised_amount = 0;
+                Self::calculate_tokens(env, campaign_id, &unsold, campaign.target_amount)
+            }
+        };
+        tokens
+            .checked_mul(Self::get_exchange_rate(env.clone(), campaign_id))
+            .unwrap_or_else(|| panic_with_error!(env, Error::Overflow))
+            / RATE_SCALE
+    }
+
+    /// Tokens bought by `amount`, walking the price tiers from the current raised amount
+    fn calculate_tokens(env: &Env, campaign_id: u64, campaign: &Campaign, amount: i128) -> i128 {
+        Self::price_tokens(env, campaign_id, campaign, amount).0
+    }
+
+    /// (tokens, spent): whole tokens bought by `amount` and what they cost
+    /// `amount - spent` is the rounding dust that buys no token
+    fn price_tokens(env: &Env, campaign_id: u64, campaign: &Campaign, amount: i128) -> (i128, i128) {
+        if let Some(decay) = Self::get_price_decay(env.clone(), campaign_id) {
+            let price = Self::decayed_price(env, campaign, &decay);
+            let tokens = amount / price;
+            return (tokens, tokens * price);
+        }
+
+        let tiers = Self::get_price_tiers(env.clone(), campaign_id);
+        let mut raised = campaign.raised_amount;
+        let mut remaining = amount;
+        let mut tokens: i128 = 0;
+        let mut spent: i128 = 0;
+
+        for tier in tiers.iter() {
+            if remaining <= 0 {
+                break;
+            }
+            if raised >= tier.up_to {
+                continue;
+            }
+            let portion = remaining.min(tier.up_to - raised);
+            let portion_tokens = portion / tier.price_per_token;
+            tokens += portion_tokens;
+            spent += portion_tokens * tier.price_per_token;
+            raised += portion;
+            remaining -= portion;
+        }
+
+        if remaining > 0 {
+            let remaining_tokens = remaining / campaign.price_per_token;
+            tokens += remaining_tokens;
+            spent += remaining_tokens * campaign.price_per_token;
+        }
+        (tokens, spent)
+    }
+
+    /// A campaign is refundable once it ended below its soft cap and nothing was released
+    fn is_refundable(env: &Env, campaign: &Campaign) -> bool {
+        let ended = !campaign.is_active || env.ledger().timestamp() > campaign.deadline;
+        ended
+            && campaign.raised_amount < campaign.soft_cap
+            && campaign.withdrawn_amount == 0
+    }
+
+    /// Add to the investor's running total and re-rank them in the bounded top-N list
+    fn update_leaderboard(env: &Env, investor: &Address, amount: i128) {
+        let total_key = Self::get_investor_total_key(investor);
+        let total: i128 = env
+            .storage()
+            .persistent()
+            .get::<_, i128>(&total_key)
+            .unwrap_or(0)
+            .checked_add(amount)
+            .unwrap_or_else(|| panic_with_error!(env, Error::Overflow));
+        env.storage().persistent().set(&total_key, &total);
+
+        let mut leaderboard: Vec<(Address, i128)> = env
+            .storage()
+            .instance()
+            .get(&KEY_LEADERBOARD)
+            .unwrap_or(Vec::new(env));
+
+        if let Some(pos) = leaderboard.iter().position(|(addr, _)| addr == *investor) {
+            leaderboard.remove(pos as u32);
         }
-        .publish(&env);
+
+        let mut insert_at = leaderboard.len();
+        for (i, (_, existing_total)) in leaderboard.iter().enumerate() {
+            if total > existing_total {
+                insert_at = i as u32;
+                break;
+            }
+        }
+
+        if insert_at < MAX_LEADERBOARD {
+            leaderboard.insert(insert_at, (investor.clone(), total));
+            if leaderboard.len() > MAX_LEADERBOARD {
+                leaderboard.pop_back();
+            }
+        }
+        env.storage().instance().set(&KEY_LEADERBOARD, &leaderboard);
     }
 
-    // =============================
-    // 💰 INVESTMENT FUNCTIONS
-    // =============================
-    
-    /// Invest in a campaign
-    pub fn invest(
-        env: Env,
+    /// Validate an investment against the campaign's status and per-investor limits
+    fn check_investment(
+        env: &Env,
         campaign_id: u64,
-        investor: Address,
+        campaign: &Campaign,
+        investor: &Address,
         amount: i128,
-    ) {
-        Self::require_initialized(&env);
-        investor.require_auth();
-
-        if amount <= 0 {
-            panic!("Invalid amount");
+    ) -> Result<(), Error> {
+        // Validate campaign status
+        if !campaign.published {
+            return Err(Error::CampaignNotPublished);
         }
 
-        // Load campaign
-        let campaign_key = Self::get_campaign_key(campaign_id);
-        let mut campaign: Campaign = env
-            .storage()
-            .persistent()
-            .get(&campaign_key)
-            .unwrap_or_else(|| panic!("Campaign not found"));
-
-        // Validate campaign status
         if !campaign.is_active {
-            panic!("Campaign inactive");
+            return Err(Error::CampaignInactive);
         }
 
         if env.ledger().timestamp() > campaign.deadline {
-            panic!("Deadline passed");
+            return Err(Error::DeadlinePassed);
+        }
+
+        // Only whitelisted investors may invest during the private phase or a KYC-gated raise
+        if (campaign.requires_whitelist || env.ledger().timestamp() < campaign.private_deadline)
+            && !Self::is_whitelisted(env.clone(), campaign_id, investor.clone())
+        {
+            return Err(Error::NotWhitelisted);
+        }
+
+        // A hard-capped campaign takes nothing past its target
+        if Self::is_fully_subscribed(campaign) {
+            return Err(Error::CampaignInactive);
+        }
+        if !campaign.allow_oversubscription
+            && amount > campaign.target_amount - campaign.raised_amount
+        {
+            return Err(Error::InvestmentTooLarge);
         }
 
         // Check investment limits
         if amount < campaign.min_investment {
-            panic!("Investment too small");
+            return Err(Error::InvestmentTooSmall);
         }
 
         // Check max investment per investor
-        if campaign.max_investment > 0 {
-            let investment_key = Self::get_investment_key(campaign_id, &investor);
+        let max_investment = Self::effective_max_investment(env, campaign_id, campaign);
+        if max_investment > 0 {
+            let investment_key = Self::get_investment_key(campaign_id, investor);
             let existing_investment: Option<Investment> = env.storage().persistent().get(&investment_key);
             
             let total_investment = match existing_investment {
-                Some(inv) => inv.amount_invested + amount,
+                Some(inv) => inv.amount_invested.checked_add(amount).ok_or(Error::Overflow)?,
                 None => amount,
             };
 
-            if total_investment > campaign.max_investment {
-                panic!("Investment too large");
+            if total_investment > max_investment {
+                return Err(Error::InvestmentTooLarge);
             }
         }
 
-        // Transfer XLM from investor to contract
-        let xlm_token_addr: Address = env.storage().instance().get(&KEY_XLM_TOKEN).unwrap();
-        let xlm_token = token::Client::new(&env, &xlm_token_addr);
-        let contract_addr = env.current_contract_address();
+        // Only investors already on the list may join once the investor cap is reached
+        if campaign.max_investors > 0
+            && Self::get_investor_count(env.clone(), campaign_id) >= campaign.max_investors
+            && !Self::has_invested(env.clone(), campaign_id, investor.clone())
+        {
+            return Err(Error::InvestorLimitReached);
+        }
+
+        // Check number of separate investments per investor (top-ups count)
+        if campaign.max_investments_per_investor > 0 {
+            let history = Self::get_investor_history(env.clone(), campaign_id, investor.clone());
+            if history.len() >= campaign.max_investments_per_investor {
+                return Err(Error::TooManyInvestments);
+            }
+        }
+
+        Ok(())
+    }
 
-        xlm_token.transfer(&investor, &contract_addr, &amount);
+    /// Issue tokens for XLM already held by the contract and update all bookkeeping
+    fn record_investment(
+        env: &Env,
+        campaign_id: u64,
+        mut campaign: Campaign,
+        investor: &Address,
+        amount: i128,
+    ) -> Result<(), Error> {
+        let campaign_key = Self::get_campaign_key(campaign_id);
+        let contract_addr = env.current_contract_address();
 
-        // Calculate tokens to mint
-        let tokens_to_mint = amount / campaign.price_per_token;
+        // Calculate tokens to mint, converting the raise token into XLM terms
+        let (tokens, spent) = Self::price_tokens(env, campaign_id, &campaign, amount);
+        let tokens_to_mint = tokens
+            .checked_mul(Self::get_exchange_rate(env.clone(), campaign_id))
+            .ok_or(Error::Overflow)?
+            / RATE_SCALE;
         if tokens_to_mint <= 0 {
-            panic!("Investment too small");
+            return Err(Error::InvestmentTooSmall);
         }
 
+        // Only whole tokens are sold: hand back the dust and record what was spent
+        let dust = amount - spent;
+        if dust > 0 {
+            let payment_token = token::Client::new(env, &Self::payment_token(env, &campaign));
+            payment_token.transfer(&contract_addr, investor, &dust);
+        }
+        let amount = spent;
+
+        // Check the cap on tokens held through this campaign
+        if campaign.max_tokens_per_investor > 0 {
+            let held = Self::get_investment(env.clone(), campaign_id, investor.clone()).tokens_received;
+            if held.checked_add(tokens_to_mint).ok_or(Error::Overflow)? > campaign.max_tokens_per_investor {
+                return Err(Error::InvestmentTooLarge);
+            }
+        }
+
+        // The tokens sold must come out of this campaign's own deposit, whatever the
+        // oversubscription setting or exchange rate
+        Self::reserve_tokens(env, campaign_id, tokens_to_mint)?;
+
         // Update campaign
-        campaign.raised_amount += amount;
+        let raised_after = campaign.raised_amount.checked_add(amount).ok_or(Error::Overflow)?;
+        let target_crossed = campaign.raised_amount < campaign.target_amount
+            && raised_after >= campaign.target_amount;
+        campaign.raised_amount = raised_after;
         env.storage().persistent().set(&campaign_key, &campaign);
 
         // Update or create investment record
-        let investment_key = Self::get_investment_key(campaign_id, &investor);
+        let investment_key = Self::get_investment_key(campaign_id, investor);
         let investment = match env.storage().persistent().get::<_, Investment>(&investment_key) {
             Some(mut existing) => {
-                existing.amount_invested += amount;
-                existing.tokens_received += tokens_to_mint;
+                existing.amount_invested =
+                    existing.amount_invested.checked_add(amount).ok_or(Error::Overflow)?;
+                existing.tokens_received =
+                    existing.tokens_received.checked_add(tokens_to_mint).ok_or(Error::Overflow)?;
                 existing
             }
             None => Investment {
                 investor: investor.clone(),
                 amount_invested: amount,
                 tokens_received: tokens_to_mint,
+                tokens_claimed: 0,
                 timestamp: env.ledger().timestamp(),
+                refunded: false,
             }
         };
         env.storage().persistent().set(&investment_key, &investment);
 
+        // Count every invest call, including top-ups
+        let tx_count_key = Self::get_tx_count_key(campaign_id);
+        let tx_count: u64 = env.storage().persistent().get(&tx_count_key).unwrap_or(0);
+        env.storage().persistent().set(&tx_count_key, &(tx_count + 1));
+
+        // Track the largest single investment for the campaign stats
+        let largest_key = Self::get_largest_investment_key(campaign_id);
+        let largest: i128 = env.storage().persistent().get(&largest_key).unwrap_or(0);
+        if amount > largest {
+            env.storage().persistent().set(&largest_key, &amount);
+        }
+
+        // Append to the investor's per-investment history
+        let history_key = Self::get_history_key(campaign_id, investor);
+        let mut history: Vec<(u64, i128, i128)> = env
+            .storage()
+            .persistent()
+            .get(&history_key)
+            .unwrap_or(Vec::new(env));
+        history.push_back((env.ledger().timestamp(), amount, tokens_to_mint));
+        env.storage().persistent().set(&history_key, &history);
+
         // Add investor to list if not already present
         let investors_key = Self::get_investors_key(campaign_id);
         let mut investors: Vec<Address> = env.storage().persistent().get(&investors_key).unwrap();
         
-        if !Self::vec_contains(&investors, &investor) {
+        if !Self::vec_contains(&investors, investor) {
             investors.push_back(investor.clone());
             env.storage().persistent().set(&investors_key, &investors);
+            env.storage()
+                .persistent()
+                .set(&Self::get_investor_count_key(campaign_id), &investors.len());
+        }
+
+        // Investments become binding once enough investors have joined
+        if !campaign.locked && campaign.min_investors > 0 && investors.len() >= campaign.min_investors {
+            campaign.locked = true;
+            env.storage().persistent().set(&campaign_key, &campaign);
+            CampaignLockedEvent {
+                campaign_id,
+                investor_count: investors.len(),
+            }
+            .publish(env);
         }
 
-        // Transfer equity tokens to investor (assumes company has pre-minted tokens to contract)
-        let equity_token = token::Client::new(&env, &campaign.equity_token_addr);
-        equity_token.transfer(&contract_addr, &investor, &tokens_to_mint);
+        // Keep the campaign and its investors' records from expiring mid-raise
+        Self::bump_campaign_ttl(env, campaign_id);
+
+        // Equity tokens, and any bonus tokens, are pulled by the investor through `claim_tokens`
+
+        // Update platform-wide investor tally and leaderboard
+        Self::update_leaderboard(env, investor, amount);
 
         // Update global stats
         let mut stats: CampaignStats = env.storage().instance().get(&KEY_STATS).unwrap();
-        stats.total_raised += amount;
+        stats.total_raised = stats.total_raised.checked_add(amount).ok_or(Error::Overflow)?;
         env.storage().instance().set(&KEY_STATS, &stats);
 
+        // Track the largest raise achieved by any campaign
+        let (_, record_amount) = Self::get_record_raise(env.clone());
+        if campaign.raised_amount > record_amount {
+            env.storage()
+                .instance()
+                .set(&KEY_RECORD_RAISE, &(campaign_id, campaign.raised_amount));
+        }
+
+        // Notify the hook contract on the investment that crosses the target
+        if target_crossed {
+            if let Some(notify_contract) = &campaign.notify_contract {
+                TargetNotifyClient::new(env, notify_contract)
+                    .on_target_reached(&campaign_id, &campaign.raised_amount);
+            }
+        }
+
         // Emit event
+        Self::record_event(env, symbol_short!("invested"), campaign_id, amount);
         InvestedEvent {
             campaign_id,
             investor: investor.clone(),
             amount,
             tokens_received: tokens_to_mint,
         }
-        .publish(&env);
-    }
-
-    // =============================
-    // 💸 WITHDRAWAL FUNCTIONS
-    // =============================
-    
-    /// Withdraw raised funds (company only, after conditions met)
-    pub fn withdraw_funds(env: Env, campaign_id: u64) {
-        Self::require_initialized(&env);
+        .publish(env);
 
-        let campaign_key = Self::get_campaign_key(campaign_id);
-        let mut campaign: Campaign = env
-            .storage()
-            .persistent()
-            .get(&campaign_key)
-            .unwrap_or_else(|| panic!("Campaign not found"));
+        // Release funds and close straight away when the company opted in
+        if target_crossed && campaign.auto_withdraw && Self::can_withdraw(env, &campaign) {
+            campaign.closed_at = env.ledger().timestamp();
+            Self::release_funds(env, campaign_id, &mut campaign);
+            Self::record_event(env, symbol_short!("closed"), campaign_id, 0);
+            CampaignClosedEvent { campaign_id }.publish(env);
+        }
 
-        campaign.company_addr.require_auth();
+        Ok(())
+    }
 
-        if !campaign.is_active {
-            panic!("Campaign inactive");
+    /// (refund, returned, kept): what `claim_refund` pays for an investment right now
+    /// Claimed tokens the investor has since passed on cannot be taken back, so the
+    /// refund shrinks pro rata to the tokens that are not returned
+    fn refund_payout(
+        env: &Env,
+        campaign: &Campaign,
+        investment: &Investment,
+    ) -> Result<(i128, i128, i128), Error> {
+        if investment.refunded || investment.amount_invested <= 0 {
+            return Ok((0, 0, 0));
         }
 
-        // Check if conditions met for withdrawal
-        let can_withdraw = campaign.raised_amount >= campaign.target_amount
-            || env.ledger().timestamp() > campaign.deadline;
+        let held = EquityTokenClient::new(env, &campaign.equity_token_addr)
+            .balance_of(&investment.investor);
+        let returned = investment.tokens_claimed.min(held);
+        let kept = investment.tokens_claimed - returned;
+        let refund = if kept > 0 {
+            investment
+                .amount_invested
+                .checked_mul(investment.tokens_received - kept)
+                .ok_or(Error::Overflow)?
+                / investment.tokens_received
+        } else {
+            investment.amount_invested
+        };
+        Ok((refund, returned, kept))
+    }
 
-        if !can_withdraw {
-            panic!("Cannot withdraw");
+    /// Bonus tokens owed on a given amount of claimed equity (0 without a bonus token)
+    fn bonus_for(env: &Env, campaign_id: u64, tokens: i128) -> Result<i128, Error> {
+        match Self::get_bonus_token(env.clone(), campaign_id) {
+            Some(bonus) => Ok(tokens.checked_mul(bonus.ratio_bps).ok_or(Error::Overflow)? / BPS_DENOMINATOR),
+            None => Ok(0),
         }
+    }
 
-        let withdraw_amount = campaign.raised_amount;
-
-        // Transfer XLM from contract to company
-        let xlm_token_addr: Address = env.storage().instance().get(&KEY_XLM_TOKEN).unwrap();
-        let xlm_token = token::Client::new(&env, &xlm_token_addr);
+    /// Take back bonus tokens from an investor who is returning the equity they came with
+    fn return_bonus(env: &Env, campaign_id: u64, investor: &Address, amount: i128) -> Result<(), Error> {
+        if amount <= 0 {
+            return Ok(());
+        }
+        let bonus = Self::get_bonus_token(env.clone(), campaign_id).unwrap();
+        let bonus_token = token::Client::new(env, &bonus.token_addr);
         let contract_addr = env.current_contract_address();
+        if !matches!(bonus_token.try_transfer(investor, &contract_addr, &amount), Ok(Ok(()))) {
+            return Err(Error::InsufficientBalance);
+        }
+        Ok(())
+    }
 
-        xlm_token.transfer(&contract_addr, &campaign.company_addr, &withdraw_amount);
+    /// Tokens released by the vesting schedule so far (all of them without a schedule)
+    fn vested_tokens(env: &Env, campaign_id: u64, investment: &Investment) -> i128 {
+        let Some(schedule) = Self::get_vesting_schedule(env.clone(), campaign_id) else {
+            return investment.tokens_received;
+        };
 
-        // Mark campaign as closed
-        campaign.is_active = false;
-        env.storage().persistent().set(&campaign_key, &campaign);
+        let elapsed = env.ledger().timestamp().saturating_sub(schedule.start);
+        if elapsed < schedule.cliff {
+            0
+        } else if elapsed >= schedule.duration {
+            investment.tokens_received
+        } else {
+            investment
+                .tokens_received
+                .checked_mul(elapsed as i128)
+                .unwrap_or_else(|| panic_with_error!(env, Error::Overflow))
+                / schedule.duration as i128
+        }
+    }
 
-        // Update stats
-        let mut stats: CampaignStats = env.storage().instance().get(&KEY_STATS).unwrap();
-        stats.active_campaigns = stats.active_campaigns.saturating_sub(1);
-        env.storage().instance().set(&KEY_STATS, &stats);
+    /// Most the campaign will take from the investor right now (i128::MAX = no limit)
+    fn investable_amount(env: &Env, campaign_id: u64, campaign: &Campaign, investor: &Address) -> i128 {
+        let room_to_target = if campaign.allow_oversubscription {
+            i128::MAX
+        } else {
+            (campaign.target_amount - campaign.raised_amount).max(0)
+        };
 
-        // Emit event
-        WithdrawnEvent {
-            campaign_id,
-            company: campaign.company_addr.clone(),
-            amount: withdraw_amount,
+        Self::remaining_allowance(env, campaign_id, campaign, investor).min(room_to_target)
+    }
+
+    /// What the investor may still add under the per-investor cap (i128::MAX = no cap)
+    fn remaining_allowance(env: &Env, campaign_id: u64, campaign: &Campaign, investor: &Address) -> i128 {
+        let max_investment = Self::effective_max_investment(env, campaign_id, campaign);
+        if max_investment <= 0 {
+            return i128::MAX;
         }
-        .publish(&env);
+        let investment = Self::get_investment(env.clone(), campaign_id, investor.clone());
+        (max_investment - investment.amount_invested).max(0)
     }
 
-    /// Emergency close campaign (admin or company)
-    pub fn close_campaign(env: Env, campaign_id: u64, caller: Address) {
-        Self::require_initialized(&env);
-        caller.require_auth();
+    fn is_fully_subscribed(campaign: &Campaign) -> bool {
+        !campaign.allow_oversubscription && campaign.raised_amount >= campaign.target_amount
+    }
 
-        let campaign_key = Self::get_campaign_key(campaign_id);
-        let mut campaign: Campaign = env
+    /// Per-investor cap currently in force (0 = no limit)
+    /// A progressive cap tightens as `raised_amount / target_amount` grows
+    fn effective_max_investment(env: &Env, campaign_id: u64, campaign: &Campaign) -> i128 {
+        let progressive: Option<ProgressiveCap> = env
             .storage()
             .persistent()
-            .get(&campaign_key)
-            .unwrap_or_else(|| panic!("Campaign not found"));
+            .get(&Self::get_progressive_cap_key(campaign_id));
 
-        // Only admin or company can close
-        let admin: Address = env.storage().instance().get(&KEY_ADMIN).unwrap();
-        if caller != admin && caller != campaign.company_addr {
-            panic!("Unauthorized");
+        let Some(cap) = progressive else {
+            return campaign.max_investment;
+        };
+
+        let filled = campaign.raised_amount.min(campaign.target_amount);
+        let current_cap = cap.start_cap
+            - (cap.start_cap - cap.end_cap)
+                .checked_mul(filled)
+                .unwrap_or_else(|| panic_with_error!(env, Error::Overflow))
+                / campaign.target_amount;
+
+        if campaign.max_investment > 0 {
+            current_cap.min(campaign.max_investment)
+        } else {
+            current_cap
         }
+    }
 
-        if campaign.is_active {
-            campaign.is_active = false;
-            env.storage().persistent().set(&campaign_key, &campaign);
+    /// Keep a campaign's entries alive using the tunable TTL constants
+    fn bump_campaign_ttl(env: &Env, campaign_id: u64) {
+        Self::extend_campaign_ttl(env, campaign_id, CAMPAIGN_TTL_THRESHOLD, CAMPAIGN_TTL_EXTEND_TO);
+    }
 
-            // Update stats
-            let mut stats: CampaignStats = env.storage().instance().get(&KEY_STATS).unwrap();
-            stats.active_campaigns = stats.active_campaigns.saturating_sub(1);
-            env.storage().instance().set(&KEY_STATS, &stats);
+    /// Extend the campaign, its configuration and every investor's records
+    fn extend_campaign_ttl(env: &Env, campaign_id: u64, threshold: u32, extend_to: u32) {
+        for key in [
+            Self::get_campaign_key(campaign_id),
+            Self::get_investors_key(campaign_id),
+            Self::get_tx_count_key(campaign_id),
+            Self::get_investor_count_key(campaign_id),
+            Self::get_largest_investment_key(campaign_id),
+            Self::get_tiers_key(campaign_id),
+            Self::get_bonus_key(campaign_id),
+            Self::get_decay_key(campaign_id),
+            Self::get_progressive_cap_key(campaign_id),
+            Self::get_vesting_key(campaign_id),
+            Self::get_splits_key(campaign_id),
+            Self::get_exchange_rate_key(campaign_id),
+            Self::get_token_deposit_key(campaign_id),
+            Self::get_tokens_sold_key(campaign_id),
+        ] {
+            Self::extend_if_present(env, &key, threshold, extend_to);
+        }
 
-            CampaignClosedEvent { campaign_id }.publish(&env);
+        for investor in Self::get_investors(env.clone(), campaign_id).iter() {
+            let investment_key = Self::get_investment_key(campaign_id, &investor);
+            Self::extend_if_present(env, &investment_key, threshold, extend_to);
+            let history_key = Self::get_history_key(campaign_id, &investor);
+            Self::extend_if_present(env, &history_key, threshold, extend_to);
         }
     }
 
-    // =============================
-    // 🔍 QUERY FUNCTIONS
-    // =============================
-    
-    /// Get campaign details
-    pub fn get_campaign(env: Env, campaign_id: u64) -> Campaign {
-        let campaign_key = Self::get_campaign_key(campaign_id);
-        env.storage()
-            .persistent()
-            .get(&campaign_key)
-            .unwrap_or_else(|| panic!("Campaign not found"))
+    fn extend_if_present<K: IntoVal<Env, Val>>(env: &Env, key: &K, threshold: u32, extend_to: u32) {
+        let storage = env.storage().persistent();
+        if storage.has(key) {
+            storage.extend_ttl(key, threshold, extend_to);
+        }
     }
 
-    /// Get investment details for an investor
-    pub fn get_investment(env: Env, campaign_id: u64, investor: Address) -> Investment {
-        let investment_key = Self::get_investment_key(campaign_id, &investor);
-        env.storage()
-            .persistent()
-            .get(&investment_key)
-            .unwrap_or(Investment {
-                investor: investor.clone(),
-                amount_invested: 0,
-                tokens_received: 0,
-                timestamp: 0,
-            })
+    /// Platform fee (bps) applied to a campaign's withdrawal
+    fn effective_fee_bps(env: &Env, campaign: &Campaign) -> u32 {
+        campaign
+            .fee_bps_override
+            .unwrap_or_else(|| env.storage().instance().get(&KEY_FEE_BPS).unwrap_or(0))
     }
 
-    /// Get all investors for a campaign
-    pub fn get_investors(env: Env, campaign_id: u64) -> Vec<Address> {
-        let investors_key = Self::get_investors_key(campaign_id);
-        env.storage()
-            .persistent()
-            .get(&investors_key)
-            .unwrap_or(Vec::new(&env))
+    /// Succeeded once the target is reached, or after the deadline if the soft cap was met
+    fn has_succeeded(env: &Env, campaign: &Campaign) -> bool {
+        campaign.raised_amount >= campaign.target_amount
+            || (env.ledger().timestamp() > campaign.deadline
+                && campaign.raised_amount >= campaign.soft_cap)
     }
 
-    /// Get investor count
-    pub fn get_investor_count(env: Env, campaign_id: u64) -> u32 {
-        let investors = Self::get_investors(env.clone(), campaign_id);
-        investors.len()
+    /// A campaign that missed its soft cap is refunded to investors, never withdrawn
+    fn can_withdraw(env: &Env, campaign: &Campaign) -> bool {
+        campaign.is_active
+            && (campaign.min_investors == 0 || campaign.locked)
+            && Self::has_succeeded(env, campaign)
     }
 
-    /// Get global stats
-    pub fn get_stats(env: Env) -> CampaignStats {
-        env.storage()
-            .instance()
-            .get(&KEY_STATS)
-            .unwrap_or(CampaignStats {
-                total_campaigns: 0,
-                active_campaigns: 0,
-                total_raised: 0,
-            })
+    /// Publish the final top-N investors by tokens held
+    fn emit_cap_table(env: &Env, campaign_id: u64) {
+        let mut entries: Vec<(Address, i128)> = Vec::new(env);
+        for investment in
+            Self::ranked_investments(env, campaign_id, MAX_CAP_TABLE, |inv| inv.tokens_received).iter()
+        {
+            entries.push_back((investment.investor, investment.tokens_received));
+        }
+
+        CapTableFinalizedEvent {
+            campaign_id,
+            entries,
+        }
+        .publish(env);
     }
 
-    /// Check if investor has invested in campaign
-    pub fn has_invested(env: Env, campaign_id: u64, investor: Address) -> bool {
-        let investment_key = Self::get_investment_key(campaign_id, &investor);
-        env.storage().persistent().has(&investment_key)
+    /// The campaign's top `limit` investments ranked by `rank_by`, highest first
+    /// Investments ranking at zero or below are left out
+    fn ranked_investments(
+        env: &Env,
+        campaign_id: u64,
+        limit: u32,
+        rank_by: fn(&Investment) -> i128,
+    ) -> Vec<Investment> {
+        let mut ranked: Vec<Investment> = Vec::new(env);
+        for investor in Self::get_investors(env.clone(), campaign_id).iter() {
+            let investment = Self::get_investment(env.clone(), campaign_id, investor);
+            let score = rank_by(&investment);
+            if score <= 0 {
+                continue;
+            }
+
+            let mut insert_at = ranked.len();
+            for (i, existing) in ranked.iter().enumerate() {
+                if score > rank_by(&existing) {
+                    insert_at = i as u32;
+                    break;
+                }
+            }
+            if insert_at < limit {
+                ranked.insert(insert_at, investment);
+                if ranked.len() > limit {
+                    ranked.pop_back();
+                }
+            }
+        }
+        ranked
     }
 
-    /// Get campaign progress (percentage)
-    pub fn get_campaign_progress(env: Env, campaign_id: u64) -> i128 {
-        let campaign = Self::get_campaign(env, campaign_id);
-        if campaign.target_amount == 0 {
-            return 0;
+    /// Token the campaign raises in, defaulting to the platform XLM token
+    fn payment_token(env: &Env, campaign: &Campaign) -> Address {
+        campaign
+            .payment_token
+            .clone()
+            .unwrap_or_else(|| env.storage().instance().get(&KEY_XLM_TOKEN).unwrap())
+    }
+
+    fn require_not_paused(env: &Env) -> Result<(), Error> {
+        if Self::is_paused(env.clone()) {
+            return Err(Error::Paused);
         }
-        (campaign.raised_amount * 100) / campaign.target_amount
+        Ok(())
     }
 
-    // =============================
-    // 🔧 HELPER FUNCTIONS
-    // =============================
-    
-    fn require_initialized(env: &Env) {
+    /// Run `f` with the reentrancy lock held
+    /// A token contract calling back into a guarded entrypoint mid-transfer gets `Reentrancy`
+    fn non_reentrant<T>(env: &Env, f: impl FnOnce() -> Result<T, Error>) -> Result<T, Error> {
+        if env.storage().instance().get(&KEY_LOCK).unwrap_or(false) {
+            return Err(Error::Reentrancy);
+        }
+        env.storage().instance().set(&KEY_LOCK, &true);
+        let result = f();
+        env.storage().instance().remove(&KEY_LOCK);
+        result
+    }
+
+    fn require_initialized(env: &Env) -> Result<(), Error> {
         if !env.storage().instance().has(&KEY_INITIALIZED) {
-            panic!("Not initialized");
+            return Err(Error::NotInitialized);
         }
+        Ok(())
     }
 
     fn get_campaign_key(campaign_id: u64) -> (&'static str, u64) {
@@ -518,10 +4009,82 @@ impl FundraisingContract {
         (("INV", campaign_id), investor.clone())
     }
 
+    fn get_commitment_key(campaign_id: u64, investor: &Address) -> ((&'static str, u64), Address) {
+        (("COMMIT", campaign_id), investor.clone())
+    }
+
+    fn get_history_key(campaign_id: u64, investor: &Address) -> ((&'static str, u64), Address) {
+        (("HIST", campaign_id), investor.clone())
+    }
+
+    fn get_token_deposit_key(campaign_id: u64) -> (&'static str, u64) {
+        ("TOKDEP", campaign_id)
+    }
+
+    fn get_tokens_sold_key(campaign_id: u64) -> (&'static str, u64) {
+        ("TOKSOLD", campaign_id)
+    }
+
+    fn get_whitelist_key(campaign_id: u64, investor: &Address) -> ((&'static str, u64), Address) {
+        (("PRIVWL", campaign_id), investor.clone())
+    }
+
+    fn get_investor_total_key(investor: &Address) -> (&'static str, Address) {
+        ("TOTAL", investor.clone())
+    }
+
+    fn get_company_campaigns_key(company: &Address) -> (&'static str, Address) {
+        ("COMPANY", company.clone())
+    }
+
+    fn get_tx_count_key(campaign_id: u64) -> (&'static str, u64) {
+        ("TXCNT", campaign_id)
+    }
+
+    fn get_tag_key(tag: &Symbol) -> (&'static str, Symbol) {
+        ("TAG", tag.clone())
+    }
+
     fn get_investors_key(campaign_id: u64) -> (&'static str, u64) {
         ("INVS", campaign_id)
     }
 
+    fn get_investor_count_key(campaign_id: u64) -> (&'static str, u64) {
+        ("INVCNT", campaign_id)
+    }
+
+    fn get_largest_investment_key(campaign_id: u64) -> (&'static str, u64) {
+        ("LARGEST", campaign_id)
+    }
+
+    fn get_vesting_key(campaign_id: u64) -> (&'static str, u64) {
+        ("VEST", campaign_id)
+    }
+
+    fn get_tiers_key(campaign_id: u64) -> (&'static str, u64) {
+        ("TIERS", campaign_id)
+    }
+
+    fn get_bonus_key(campaign_id: u64) -> (&'static str, u64) {
+        ("BONUS", campaign_id)
+    }
+
+    fn get_decay_key(campaign_id: u64) -> (&'static str, u64) {
+        ("DECAY", campaign_id)
+    }
+
+    fn get_progressive_cap_key(campaign_id: u64) -> (&'static str, u64) {
+        ("PCAP", campaign_id)
+    }
+
+    fn get_exchange_rate_key(campaign_id: u64) -> (&'static str, u64) {
+        ("FXRATE", campaign_id)
+    }
+
+    fn get_splits_key(campaign_id: u64) -> (&'static str, u64) {
+        ("SPLITS", campaign_id)
+    }
+
     fn vec_contains(vec: &Vec<Address>, addr: &Address) -> bool {
         for i in 0..vec.len() {
             if let Some(item) = vec.get(i) {
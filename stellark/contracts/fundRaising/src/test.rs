@@ -0,0 +1,2825 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{
+    testutils::{
+        storage::{Instance as _, Persistent as _},
+        Address as _, Events as _, Ledger,
+    },
+    token::{StellarAssetClient, TokenClient},
+    xdr, Bytes, Map, String, TryFromVal,
+};
+
+const DAY: u64 = 86_400;
+/// Equity tokens the company deposits for each campaign it launches
+const CAMPAIGN_DEPOSIT: i128 = 100_000;
+
+/// Register an EquityToken of 1,000,000 tokens (20% of the company) owned by `company`
+fn register_equity_token(env: &Env, company: &Address) -> Address {
+    let token_id = env.register(equity_token::EquityToken, ());
+    equity_token::EquityTokenClient::new(env, &token_id).init_company(
+        &String::from_str(env, "Stellark Labs"),
+        &String::from_str(env, "STLK"),
+        &1_000_000,
+        company,
+        &20,
+        &String::from_str(env, "Equity in Stellark Labs"),
+        &10,
+        &10_000,
+        &false,
+        &7,
+    );
+    token_id
+}
+
+// Smallest wasm the host accepts: an empty module carrying only the env meta section
+const EMPTY_WASM: &[u8] =
+    b"\0asm\x01\0\0\0\0\x1e\x11contractenvmetav0\0\0\0\0\0\0\0\x17\0\0\0\0";
+
+/// Hash of the wasm a contract instance currently runs
+fn wasm_hash_of(env: &Env, contract: &Address) -> [u8; 32] {
+    let contract = xdr::ScAddress::from(contract);
+    env.to_snapshot()
+        .ledger
+        .ledger_entries
+        .iter()
+        .find_map(|(_, (entry, _))| match &entry.data {
+            xdr::LedgerEntryData::ContractData(data)
+                if data.contract == contract && data.key == xdr::ScVal::LedgerKeyContractInstance =>
+            {
+                match &data.val {
+                    xdr::ScVal::ContractInstance(xdr::ScContractInstance {
+                        executable: xdr::ContractExecutable::Wasm(hash),
+                        ..
+                    }) => Some(hash.0),
+                    _ => None,
+                }
+            }
+            _ => None,
+        })
+        .unwrap()
+}
+
+struct Setup<'a> {
+    env: Env,
+    client: FundraisingContractClient<'a>,
+    xlm: Address,
+    equity: Address,
+    company: Address,
+    admin: Address,
+}
+
+impl<'a> Setup<'a> {
+    fn new() -> Self {
+        let env = Env::default();
+        env.mock_all_auths();
+        env.ledger().with_mut(|l| l.timestamp = 1_000);
+
+        let contract_id = env.register(FundraisingContract, ());
+        let client = FundraisingContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let xlm = env.register_stellar_asset_contract_v2(admin.clone()).address();
+        let company = Address::generate(&env);
+
+        client.initialize(&admin, &xlm, &0, &admin);
+
+        let equity = register_equity_token(&env, &company);
+
+        Setup {
+            env,
+            client,
+            xlm,
+            equity,
+            company,
+            admin,
+        }
+    }
+
+    /// Live campaign: 10,000 target, price 10, 30 day deadline, 100 minimum, no cap
+    fn create_default_campaign(&self, campaign_id: u64) {
+        self.create_draft_campaign(campaign_id);
+        self.launch(campaign_id);
+    }
+
+    /// Publish, fund and activate a draft campaign
+    fn launch(&self, campaign_id: u64) {
+        self.client.publish_campaign(&campaign_id);
+        self.client.deposit_tokens(&campaign_id, &CAMPAIGN_DEPOSIT);
+        self.client.activate_campaign(&campaign_id);
+    }
+
+    fn create_draft_campaign(&self, campaign_id: u64) {
+        self.client.create_campaign(
+            &campaign_id,
+            &self.company,
+            &self.equity,
+            &10_000,
+            &10,
+            &(self.env.ledger().timestamp() + 30 * DAY),
+            &100,
+            &0,
+            &10_000,
+        );
+    }
+
+    fn investor(&self, xlm_balance: i128) -> Address {
+        let investor = Address::generate(&self.env);
+        StellarAssetClient::new(&self.env, &self.xlm).mint(&investor, &xlm_balance);
+        investor
+    }
+
+    fn advance(&self, seconds: u64) {
+        self.env.ledger().with_mut(|l| l.timestamp += seconds);
+    }
+}
+
+#[test]
+fn test_fill_rate_over_time() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    let investor = s.investor(10_000);
+
+    assert_eq!(s.client.get_fill_rate(&1), 0);
+
+    s.advance(2 * DAY);
+    s.client.invest(&1, &investor, &2_000, &false);
+
+    // 20% of target over 2 days = 1,000 bps per day
+    assert_eq!(s.client.get_fill_rate(&1), 1_000);
+
+    s.advance(2 * DAY);
+    assert_eq!(s.client.get_fill_rate(&1), 500);
+}
+
+#[test]
+fn test_initialize_and_create_in_one_call() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(FundraisingContract, ());
+    let client = FundraisingContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    let xlm = env.register_stellar_asset_contract_v2(admin.clone()).address();
+    let equity = env.register_stellar_asset_contract_v2(admin.clone()).address();
+    let company = Address::generate(&env);
+
+    client.initialize_and_create(
+        &admin, &xlm, &7, &company, &equity, &10_000, &10, &(30 * DAY), &100, &0,
+    );
+
+    let stats = client.get_stats();
+    assert_eq!(stats.total_campaigns, 1);
+    assert_eq!(stats.active_campaigns, 0);
+    let campaign = client.get_campaign(&7);
+    assert_eq!(campaign.company_addr, company);
+    assert_eq!(campaign.target_amount, 10_000);
+
+    // A second bootstrap must fail since the contract is already initialized
+    let result = client.try_initialize_and_create(
+        &admin, &xlm, &8, &company, &equity, &10_000, &10, &(30 * DAY), &100, &0,
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_create_campaign_rejects_price_above_min_investment() {
+    let s = Setup::new();
+
+    let result = s.client.try_create_campaign(
+        &1,
+        &s.company,
+        &s.equity,
+        &10_000,
+        &500,
+        &(s.env.ledger().timestamp() + 30 * DAY),
+        &100,
+        &0,
+        &10_000,
+    );
+    assert_eq!(result, Err(Ok(Error::InvalidAmount.into())));
+}
+
+#[test]
+fn test_withdrawable_before_and_after_withdrawal() {
+    let s = Setup::new();
+    s.create_draft_campaign(1);
+    s.client.set_allow_oversubscription(&1, &true);
+    s.launch(1);
+    let investor = s.investor(20_000);
+
+    s.client.invest(&1, &investor, &4_000, &false);
+    assert_eq!(s.client.get_withdrawable(&1), 0);
+
+    s.client.invest(&1, &investor, &8_000, &false);
+    assert_eq!(s.client.get_withdrawable(&1), 12_000);
+
+    s.client.withdraw_funds(&1);
+    assert_eq!(s.client.get_withdrawable(&1), 0);
+    assert_eq!(s.client.get_campaign(&1).withdrawn_amount, 12_000);
+}
+
+#[test]
+fn test_recent_events_in_order() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    let investor = s.investor(20_000);
+
+    s.client.invest(&1, &investor, &3_000, &false);
+    s.client.invest(&1, &investor, &7_000, &false);
+    s.client.withdraw_funds(&1);
+
+    let events = s.client.get_recent_events(&3);
+    assert_eq!(events.len(), 3);
+    assert_eq!(events.get(0).unwrap().kind, symbol_short!("invested"));
+    assert_eq!(events.get(0).unwrap().amount, 3_000);
+    assert_eq!(events.get(1).unwrap().amount, 7_000);
+    assert_eq!(events.get(2).unwrap().kind, symbol_short!("withdrawn"));
+    assert_eq!(events.get(2).unwrap().amount, 10_000);
+
+    assert_eq!(s.client.get_recent_events(&100).len(), 5);
+}
+
+#[test]
+fn test_recent_events_bounded() {
+    let s = Setup::new();
+    for campaign_id in 0..12 {
+        s.create_draft_campaign(campaign_id);
+    }
+
+    let events = s.client.get_recent_events(&100);
+    assert_eq!(events.len(), 10);
+    assert_eq!(events.get(0).unwrap().campaign_id, 2);
+    assert_eq!(events.get(9).unwrap().campaign_id, 11);
+}
+
+#[test]
+fn test_invest_requires_published_campaign() {
+    let s = Setup::new();
+    s.create_draft_campaign(1);
+    let investor = s.investor(10_000);
+
+    assert!(!s.client.get_campaign(&1).published);
+    let result = s.client.try_invest(&1, &investor, &1_000, &false);
+    assert_eq!(result, Err(Ok(Error::CampaignNotPublished)));
+
+    s.launch(1);
+    assert!(s.client.get_campaign(&1).published);
+    s.client.invest(&1, &investor, &1_000, &false);
+    assert_eq!(s.client.get_campaign(&1).raised_amount, 1_000);
+}
+
+#[test]
+fn test_price_impact_with_tiers() {
+    let s = Setup::new();
+    s.create_draft_campaign(1);
+
+    // First 2,000 raised at 10, next 4,000 at 20, then the base price of 10
+    let mut tiers = Vec::new(&s.env);
+    tiers.push_back(PriceTier { up_to: 2_000, price_per_token: 10 });
+    tiers.push_back(PriceTier { up_to: 6_000, price_per_token: 20 });
+    s.client.set_price_tiers(&1, &tiers);
+    s.launch(1);
+
+    assert_eq!(s.client.get_price_impact(&1, &1_000), 0);
+
+    // 2,000 at 10 + 2,000 at 20 = 300 tokens, blended price 13.33 vs 10
+    assert_eq!(s.client.get_price_impact(&1, &4_000), 3_333);
+
+    let result = s.client.try_get_price_impact(&1, &(i128::MAX / 2));
+    assert_eq!(result, Err(Ok(Error::Overflow.into())));
+
+    let investor = s.investor(10_000);
+    s.client.invest(&1, &investor, &4_000, &false);
+    assert_eq!(s.client.get_investment(&1, &investor).tokens_received, 300);
+}
+
+#[test]
+fn test_price_tiers_only_settable_on_draft() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+
+    let mut tiers = Vec::new(&s.env);
+    tiers.push_back(PriceTier { up_to: 2_000, price_per_token: 10 });
+    let result = s.client.try_set_price_tiers(&1, &tiers);
+    assert_eq!(result, Err(Ok(Error::CampaignAlreadyPublished.into())));
+}
+
+#[test]
+fn test_bonus_token_delivered_in_proportion() {
+    let s = Setup::new();
+    s.create_draft_campaign(1);
+
+    let bonus = s
+        .env
+        .register_stellar_asset_contract_v2(Address::generate(&s.env))
+        .address();
+    StellarAssetClient::new(&s.env, &bonus).mint(&s.client.address, &1_000_000);
+
+    // Half a bonus token per equity token
+    s.client.set_bonus_token(&1, &bonus, &5_000);
+    s.launch(1);
+
+    let investor = s.investor(10_000);
+    s.client.invest(&1, &investor, &2_000, &false);
+    s.client.claim_tokens(&1, &investor);
+
+    assert_eq!(s.client.get_my_token_balance(&1, &investor), 200);
+    assert_eq!(TokenClient::new(&s.env, &bonus).balance(&investor), 100);
+}
+
+#[test]
+fn test_bonus_token_returned_on_exit() {
+    let s = Setup::new();
+    s.create_draft_campaign(1);
+    s.client.set_min_investors(&1, &2);
+
+    let bonus = s
+        .env
+        .register_stellar_asset_contract_v2(Address::generate(&s.env))
+        .address();
+    StellarAssetClient::new(&s.env, &bonus).mint(&s.client.address, &1_000_000);
+    let bonus_token = TokenClient::new(&s.env, &bonus);
+    s.client.set_bonus_token(&1, &bonus, &5_000);
+    s.launch(1);
+
+    // Looping invest -> exit never drains the bonus pool
+    let investor = s.investor(10_000);
+    for _ in 0..5 {
+        s.client.invest(&1, &investor, &2_000, &false);
+        s.client.exit_investment(&1, &investor);
+    }
+    assert_eq!(bonus_token.balance(&investor), 0);
+    assert_eq!(bonus_token.balance(&s.client.address), 1_000_000);
+
+    // Claimed tokens take their bonus back out with them
+    s.client.invest(&1, &investor, &2_000, &false);
+    s.client.claim_tokens(&1, &investor);
+    assert_eq!(bonus_token.balance(&investor), 100);
+    s.client.exit_investment(&1, &investor);
+    assert_eq!(bonus_token.balance(&investor), 0);
+    assert_eq!(bonus_token.balance(&s.client.address), 1_000_000);
+
+    // Exiting fails while the bonus is no longer held
+    s.client.invest(&1, &investor, &2_000, &false);
+    s.client.claim_tokens(&1, &investor);
+    let friend = Address::generate(&s.env);
+    bonus_token.transfer(&investor, &friend, &100);
+    let result = s.client.try_exit_investment(&1, &investor);
+    assert_eq!(result, Err(Ok(Error::InsufficientBalance.into())));
+}
+
+#[test]
+fn test_bonus_token_returned_on_refund() {
+    let s = Setup::new();
+    s.create_draft_campaign(1);
+
+    let bonus = s
+        .env
+        .register_stellar_asset_contract_v2(Address::generate(&s.env))
+        .address();
+    StellarAssetClient::new(&s.env, &bonus).mint(&s.client.address, &1_000_000);
+    let bonus_token = TokenClient::new(&s.env, &bonus);
+    s.client.set_bonus_token(&1, &bonus, &5_000);
+    s.launch(1);
+
+    let alice = s.investor(10_000);
+    let bob = s.investor(10_000);
+    s.client.invest(&1, &alice, &2_000, &false);
+    s.client.claim_tokens(&1, &alice);
+    s.client.invest(&1, &bob, &1_000, &false);
+    s.client.close_campaign(&1, &s.company);
+
+    s.client.claim_refund(&1, &alice);
+    s.client.claim_refund(&1, &bob);
+    assert_eq!(bonus_token.balance(&alice), 0);
+    assert_eq!(bonus_token.balance(&bob), 0);
+    assert_eq!(bonus_token.balance(&s.client.address), 1_000_000);
+}
+
+#[test]
+fn test_project_days_to_target() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    let investor = s.investor(10_000);
+
+    assert_eq!(s.client.project_days_to_target(&1), u64::MAX);
+
+    // 10% per day for 2 days leaves 8 days to go
+    s.advance(DAY);
+    s.client.invest(&1, &investor, &1_000, &false);
+    s.advance(DAY);
+    s.client.invest(&1, &investor, &1_000, &false);
+    assert_eq!(s.client.get_fill_rate(&1), 1_000);
+    assert_eq!(s.client.project_days_to_target(&1), 8);
+
+    s.client.invest(&1, &investor, &8_000, &false);
+    assert_eq!(s.client.project_days_to_target(&1), 0);
+}
+
+#[test]
+fn test_refund_within_window_then_sweep_after() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    let alice = s.investor(10_000);
+    let bob = s.investor(10_000);
+    let xlm = TokenClient::new(&s.env, &s.xlm);
+
+    s.client.invest(&1, &alice, &2_000, &false);
+    s.client.invest(&1, &bob, &3_000, &false);
+    s.client.close_campaign(&1, &s.company);
+
+    let campaign = s.client.get_campaign(&1);
+    assert_eq!(campaign.refund_deadline, s.env.ledger().timestamp() + 30 * DAY);
+
+    // Sweeping is not allowed while the refund window is open
+    let result = s.client.try_sweep_unclaimed(&1);
+    assert_eq!(result, Err(Ok(Error::RefundWindowOpen.into())));
+
+    s.client.claim_refund(&1, &alice);
+    assert_eq!(xlm.balance(&alice), 10_000);
+    assert!(s.client.get_investment(&1, &alice).refunded);
+
+    // Refunds come off the platform total and the leaderboard
+    assert_eq!(s.client.get_stats().total_raised, 3_000);
+    let top = s.client.get_top_investors(&2);
+    assert_eq!(top.get(0).unwrap(), (bob.clone(), 3_000));
+    assert_eq!(top.get(1).unwrap(), (alice.clone(), 0));
+
+    // Double refunds are rejected
+    let result = s.client.try_claim_refund(&1, &alice);
+    assert_eq!(result, Err(Ok(Error::RefundUnavailable)));
+
+    s.advance(30 * DAY + 1);
+    let result = s.client.try_claim_refund(&1, &bob);
+    assert_eq!(result, Err(Ok(Error::RefundWindowClosed)));
+
+    let token = equity_token::EquityTokenClient::new(&s.env, &s.equity);
+    let company_tokens = token.balance_of(&s.company);
+    s.client.sweep_unclaimed(&1);
+    assert_eq!(xlm.balance(&s.client.address), 0);
+
+    // The unsold deposit goes back to the company; bob's 300 unrefunded tokens stay sold
+    assert_eq!(token.balance_of(&s.company), company_tokens + CAMPAIGN_DEPOSIT - 300);
+    assert_eq!(s.client.get_token_deposit(&1), 300);
+    assert_eq!(s.client.tokens_remaining(&1), 0);
+}
+
+#[test]
+fn test_refund_burns_tokens_when_registered_burner() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    let token = equity_token::EquityTokenClient::new(&s.env, &s.equity);
+    token.set_burner(&s.client.address);
+    let alice = s.investor(10_000);
+
+    s.client.invest(&1, &alice, &2_000, &false);
+    s.client.claim_tokens(&1, &alice);
+    s.client.close_campaign(&1, &s.company);
+
+    let supply_before = token.get_company_info().total_supply;
+    let escrowed_before = token.balance_of(&s.client.address);
+    s.client.claim_refund(&1, &alice);
+
+    assert_eq!(token.get_company_info().total_supply, supply_before - 200);
+    assert_eq!(token.balance_of(&alice), 0);
+    assert_eq!(token.balance_of(&s.client.address), escrowed_before);
+}
+
+#[test]
+fn test_refund_shrinks_for_tokens_passed_on() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    let token = equity_token::EquityTokenClient::new(&s.env, &s.equity);
+    token.set_burner(&s.client.address);
+    let alice = s.investor(10_000);
+    let bob = s.investor(10_000);
+    let friend = Address::generate(&s.env);
+
+    s.client.invest(&1, &alice, &2_000, &false);
+    s.client.claim_tokens(&1, &alice);
+    token.transfer(&alice, &friend, &50);
+    s.client.invest(&1, &bob, &1_000, &false);
+    s.client.claim_tokens(&1, &bob);
+    token.transfer(&bob, &friend, &100);
+    s.client.close_campaign(&1, &s.company);
+
+    // 150 of 200 tokens come back, so 3/4 of the 2,000 is refunded
+    let supply_before = token.get_company_info().total_supply;
+    s.client.claim_refund(&1, &alice);
+    assert_eq!(TokenClient::new(&s.env, &s.xlm).balance(&alice), 8_000 + 1_500);
+    assert_eq!(token.get_company_info().total_supply, supply_before - 150);
+    assert_eq!(token.balance_of(&friend), 150);
+    assert_eq!(s.client.get_campaign(&1).refunded_amount, 1_500);
+
+    // With every token passed on, nothing is refundable
+    let result = s.client.try_claim_refund(&1, &bob);
+    assert_eq!(result, Err(Ok(Error::RefundUnavailable)));
+}
+
+#[test]
+fn test_refund_rejected_for_successful_campaign() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    let investor = s.investor(20_000);
+
+    s.client.invest(&1, &investor, &10_000, &false);
+    s.client.close_campaign(&1, &s.company);
+
+    let result = s.client.try_claim_refund(&1, &investor);
+    assert_eq!(result, Err(Ok(Error::RefundUnavailable)));
+}
+
+#[test]
+fn test_spender_allowance_reports_token_allowance() {
+    let s = Setup::new();
+    let investor = s.investor(10_000);
+
+    assert_eq!(s.client.get_spender_allowance(&s.xlm, &investor), 0);
+
+    TokenClient::new(&s.env, &s.xlm).approve(&investor, &s.client.address, &2_500, &1_000);
+    assert_eq!(s.client.get_spender_allowance(&s.xlm, &investor), 2_500);
+}
+
+#[test]
+fn test_investor_history_records_each_investment() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    let investor = s.investor(10_000);
+
+    s.client.invest(&1, &investor, &1_000, &false);
+    s.advance(DAY);
+    s.client.invest(&1, &investor, &500, &false);
+
+    let history = s.client.get_investor_history(&1, &investor);
+    assert_eq!(history.len(), 2);
+    assert_eq!(history.get(0).unwrap(), (1_000, 1_000, 100));
+    assert_eq!(history.get(1).unwrap(), (1_000 + DAY, 500, 50));
+
+    let investment = s.client.get_investment(&1, &investor);
+    assert_eq!(investment.amount_invested, 1_500);
+}
+
+#[test]
+fn test_invest_refunds_rounding_dust() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+
+    let investor = s.investor(5_000);
+    s.client.invest(&1, &investor, &2_005, &false);
+
+    let xlm = TokenClient::new(&s.env, &s.xlm);
+    assert_eq!(xlm.balance(&investor), 3_000);
+    assert_eq!(s.client.get_campaign(&1).raised_amount, 2_000);
+
+    let investment = s.client.get_investment(&1, &investor);
+    assert_eq!(investment.amount_invested, 2_000);
+    assert_eq!(investment.tokens_received, 200);
+}
+
+#[test]
+fn test_withdraw_to_two_way_split() {
+    let s = Setup::new();
+    s.create_draft_campaign(1);
+    let founder_a = Address::generate(&s.env);
+    let founder_b = Address::generate(&s.env);
+
+    let mut splits = Vec::new(&s.env);
+    splits.push_back((founder_a.clone(), 3_300));
+    splits.push_back((founder_b.clone(), 6_700));
+    s.client.set_withdraw_splits(&1, &splits);
+    s.client.set_allow_oversubscription(&1, &true);
+    s.launch(1);
+
+    let investor = s.investor(20_000);
+    s.client.invest(&1, &investor, &10_010, &false);
+    s.client.withdraw_funds(&1);
+
+    let xlm = TokenClient::new(&s.env, &s.xlm);
+    assert_eq!(xlm.balance(&founder_a), 3_303);
+    assert_eq!(xlm.balance(&founder_b), 6_707);
+    assert_eq!(xlm.balance(&s.company), 0);
+}
+
+#[test]
+fn test_withdraw_splits_must_sum_to_full() {
+    let s = Setup::new();
+    s.create_draft_campaign(1);
+
+    let mut splits = Vec::new(&s.env);
+    splits.push_back((Address::generate(&s.env), 5_000));
+    splits.push_back((Address::generate(&s.env), 4_000));
+    let result = s.client.try_set_withdraw_splits(&1, &splits);
+    assert_eq!(result, Err(Ok(Error::InvalidAmount.into())));
+}
+
+#[test]
+fn test_token_balance_matches_equity_token() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    let investor = s.investor(10_000);
+
+    s.client.invest(&1, &investor, &2_000, &false);
+    s.client.claim_tokens(&1, &investor);
+
+    let token = equity_token::EquityTokenClient::new(&s.env, &s.equity);
+    assert_eq!(token.balance_of(&investor), 200);
+    assert_eq!(s.client.get_my_token_balance(&1, &investor), token.balance_of(&investor));
+}
+
+#[test]
+fn test_tokens_remaining_and_max_investable() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    let investor = s.investor(10_000);
+
+    assert_eq!(s.client.tokens_remaining(&1), CAMPAIGN_DEPOSIT);
+    assert_eq!(s.client.max_investable(&1), 10_000);
+
+    // Owed tokens count as distributed whether or not they were claimed yet
+    s.client.invest(&1, &investor, &2_000, &false);
+    assert_eq!(s.client.tokens_remaining(&1), CAMPAIGN_DEPOSIT - 200);
+    s.client.claim_tokens(&1, &investor);
+    assert_eq!(s.client.tokens_remaining(&1), CAMPAIGN_DEPOSIT - 200);
+    assert_eq!(s.client.max_investable(&1), 8_000);
+
+    // Another round's deposit in the same token is not available to this one
+    s.create_default_campaign(2);
+    assert_eq!(s.client.tokens_remaining(&1), CAMPAIGN_DEPOSIT - 200);
+    assert_eq!(s.client.tokens_remaining(&2), CAMPAIGN_DEPOSIT);
+
+    // Unknown campaigns read as empty rather than panicking
+    assert_eq!(s.client.tokens_remaining(&9), 0);
+    assert_eq!(s.client.max_investable(&9), 0);
+}
+
+#[test]
+fn test_max_investments_per_investor() {
+    let s = Setup::new();
+    s.create_draft_campaign(1);
+    s.client.set_max_investments_per_investor(&1, &2);
+    s.launch(1);
+    let investor = s.investor(10_000);
+
+    s.client.invest(&1, &investor, &100, &false);
+    s.client.invest(&1, &investor, &100, &false);
+    let result = s.client.try_invest(&1, &investor, &100, &false);
+    assert_eq!(result, Err(Ok(Error::TooManyInvestments)));
+
+    // Other investors are unaffected
+    let other = s.investor(10_000);
+    s.client.invest(&1, &other, &100, &false);
+}
+
+#[test]
+fn test_top_investors_across_campaigns() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    s.create_default_campaign(2);
+    let alice = s.investor(10_000);
+    let bob = s.investor(10_000);
+    let carol = s.investor(10_000);
+
+    s.client.invest(&1, &alice, &1_000, &false);
+    s.client.invest(&1, &bob, &3_000, &false);
+    s.client.invest(&2, &carol, &2_000, &false);
+    s.client.invest(&2, &alice, &2_500, &false);
+
+    let top = s.client.get_top_investors(&10);
+    assert_eq!(top.len(), 3);
+    assert_eq!(top.get(0).unwrap(), (alice.clone(), 3_500));
+    assert_eq!(top.get(1).unwrap(), (bob, 3_000));
+    assert_eq!(top.get(2).unwrap(), (carol, 2_000));
+
+    assert_eq!(s.client.get_top_investors(&1).len(), 1);
+}
+
+#[test]
+fn test_detects_orphaned_investments() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    s.create_default_campaign(2);
+    let investor = s.investor(10_000);
+
+    s.client.invest(&1, &investor, &1_000, &false);
+    assert!(!s.client.has_orphaned_investments(&1));
+
+    // Simulate a campaign entry lost while its investments remain
+    s.env.as_contract(&s.client.address, || {
+        s.env
+            .storage()
+            .persistent()
+            .remove(&FundraisingContract::get_campaign_key(1));
+    });
+    assert!(s.client.has_orphaned_investments(&1));
+
+    // A campaign without investments is never orphaned
+    assert!(!s.client.has_orphaned_investments(&2));
+}
+
+#[contract]
+struct MockNotify;
+
+#[contractimpl]
+impl MockNotify {
+    pub fn on_target_reached(env: Env, campaign_id: u64, raised_amount: i128) {
+        let calls: u32 = env.storage().instance().get(&symbol_short!("calls")).unwrap_or(0);
+        env.storage().instance().set(&symbol_short!("calls"), &(calls + 1));
+        env.storage().instance().set(&symbol_short!("last"), &(campaign_id, raised_amount));
+    }
+
+    pub fn calls(env: Env) -> u32 {
+        env.storage().instance().get(&symbol_short!("calls")).unwrap_or(0)
+    }
+
+    pub fn last(env: Env) -> (u64, i128) {
+        env.storage().instance().get(&symbol_short!("last")).unwrap()
+    }
+}
+
+#[test]
+fn test_notify_contract_called_once_on_target_crossing() {
+    let s = Setup::new();
+    s.create_draft_campaign(1);
+    let notify_id = s.env.register(MockNotify, ());
+    let notify = MockNotifyClient::new(&s.env, &notify_id);
+    s.client.set_notify_contract(&1, &Some(notify_id.clone()));
+    s.client.set_allow_oversubscription(&1, &true);
+    s.launch(1);
+    let investor = s.investor(20_000);
+
+    s.client.invest(&1, &investor, &6_000, &false);
+    assert_eq!(notify.calls(), 0);
+
+    s.client.invest(&1, &investor, &5_000, &false);
+    assert_eq!(notify.calls(), 1);
+    assert_eq!(notify.last(), (1, 11_000));
+
+    s.client.invest(&1, &investor, &1_000, &false);
+    assert_eq!(notify.calls(), 1);
+}
+
+#[test]
+fn test_refund_status_flips_after_refund() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    let investor = s.investor(10_000);
+
+    s.client.invest(&1, &investor, &2_000, &false);
+    assert_eq!(s.client.get_refund_status(&1, &investor), (false, 0));
+
+    s.client.close_campaign(&1, &s.company);
+    assert_eq!(s.client.get_refund_status(&1, &investor), (false, 2_000));
+
+    s.client.claim_refund(&1, &investor);
+    assert_eq!(s.client.get_refund_status(&1, &investor), (true, 0));
+}
+
+#[test]
+fn test_refund_status_and_obligations_match_pro_rata_refund() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    let token = equity_token::EquityTokenClient::new(&s.env, &s.equity);
+    let alice = s.investor(10_000);
+    let bob = s.investor(10_000);
+    let friend = Address::generate(&s.env);
+
+    s.client.invest(&1, &alice, &2_000, &false);
+    s.client.invest(&1, &bob, &1_000, &false);
+    s.client.claim_tokens(&1, &alice);
+    s.client.close_campaign(&1, &s.company);
+
+    // Moving a quarter of the claimed tokens away shrinks the refund to match
+    token.transfer(&alice, &friend, &50);
+    assert_eq!(s.client.get_refund_status(&1, &alice), (false, 1_500));
+    assert_eq!(s.client.get_obligations().0, 2_500);
+
+    s.client.claim_refund(&1, &alice);
+    assert_eq!(TokenClient::new(&s.env, &s.xlm).balance(&alice), 8_000 + 1_500);
+    assert_eq!(s.client.get_obligations().0, 1_000);
+}
+
+#[test]
+fn test_price_decay_over_campaign() {
+    let s = Setup::new();
+    s.create_draft_campaign(1);
+    s.client.set_price_decay(&1, &20, &10);
+    s.launch(1);
+    let investor = s.investor(10_000);
+
+    assert_eq!(s.client.get_current_price(&1), 20);
+
+    s.advance(15 * DAY);
+    assert_eq!(s.client.get_current_price(&1), 15);
+    s.client.invest(&1, &investor, &1_500, &false);
+    assert_eq!(s.client.get_investment(&1, &investor).tokens_received, 100);
+
+    s.advance(12 * DAY);
+    assert_eq!(s.client.get_current_price(&1), 11);
+
+    s.advance(3 * DAY);
+    assert_eq!(s.client.get_current_price(&1), 10);
+}
+
+#[test]
+fn test_get_campaigns_skips_missing_ids() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    s.client.create_campaign(
+        &3,
+        &s.company,
+        &s.equity,
+        &20_000,
+        &10,
+        &(s.env.ledger().timestamp() + 30 * DAY),
+        &100,
+        &0,
+        &20_000,
+    );
+
+    let mut ids = Vec::new(&s.env);
+    ids.push_back(3);
+    ids.push_back(2);
+    ids.push_back(1);
+
+    let campaigns = s.client.get_campaigns(&ids);
+    assert_eq!(campaigns.len(), 2);
+    assert_eq!(campaigns.get(0).unwrap().target_amount, 20_000);
+    assert_eq!(campaigns.get(1).unwrap().target_amount, 10_000);
+}
+
+#[test]
+fn test_campaign_cannot_sell_too_much_equity() {
+    let s = Setup::new();
+
+    // 1,000,000 tokens represent 20% of the company; allow selling at most 5%
+    s.client.set_max_sellable_bps(&500);
+
+    // 300,000 tokens = 6% of the company
+    let deadline = s.env.ledger().timestamp() + 30 * DAY;
+    let result = s.client.try_create_campaign(
+        &1, &s.company, &s.equity, &3_000_000, &10, &deadline, &100, &0, &3_000_000,
+    );
+    assert_eq!(result, Err(Ok(Error::EquityCapExceeded.into())));
+
+    // 200,000 tokens = 4% of the company
+    s.client.create_campaign(
+        &1, &s.company, &s.equity, &2_000_000, &10, &deadline, &100, &0, &2_000_000,
+    );
+}
+
+#[test]
+fn test_investment_hash_stable_and_changes_on_top_up() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    let investor = s.investor(10_000);
+
+    s.client.invest(&1, &investor, &1_000, &false);
+    let first = s.client.get_investment_hash(&1, &investor);
+    assert_eq!(s.client.get_investment_hash(&1, &investor), first);
+
+    s.client.invest(&1, &investor, &500, &false);
+    assert_ne!(s.client.get_investment_hash(&1, &investor), first);
+}
+
+#[test]
+fn test_company_dashboard_aggregates_campaigns() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    s.create_default_campaign(2);
+    let alice = s.investor(20_000);
+    let bob = s.investor(20_000);
+
+    s.client.invest(&1, &alice, &6_000, &false);
+    s.client.invest(&1, &bob, &4_000, &false);
+    s.client.invest(&2, &alice, &1_500, &false);
+    s.client.withdraw_funds(&1);
+
+    let dashboard = s.client.get_company_dashboard(&s.company);
+    assert_eq!(dashboard.total_campaigns, 2);
+    assert_eq!(dashboard.active, 1);
+    assert_eq!(dashboard.succeeded, 1);
+    assert_eq!(dashboard.total_raised, 11_500);
+    assert_eq!(dashboard.total_investors, 3);
+
+    // Reaching the soft cap by the deadline counts as a success too
+    s.client.create_campaign(
+        &3,
+        &s.company,
+        &s.equity,
+        &10_000,
+        &10,
+        &(s.env.ledger().timestamp() + 30 * DAY),
+        &100,
+        &0,
+        &5_000,
+    );
+    s.launch(3);
+    s.client.invest(&3, &bob, &5_000, &false);
+    assert_eq!(s.client.get_company_dashboard(&s.company).succeeded, 1);
+    s.advance(31 * DAY);
+    assert_eq!(s.client.get_company_dashboard(&s.company).succeeded, 2);
+}
+
+#[test]
+fn test_activation_requires_token_deposit() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(FundraisingContract, ());
+    let client = FundraisingContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    let xlm = env.register_stellar_asset_contract_v2(admin.clone()).address();
+    let company = Address::generate(&env);
+    let equity = register_equity_token(&env, &company);
+    let investor = Address::generate(&env);
+    StellarAssetClient::new(&env, &xlm).mint(&investor, &10_000);
+
+    client.initialize(&admin, &xlm, &0, &admin);
+    client.create_campaign(&1, &company, &equity, &10_000, &10, &(30 * DAY), &100, &0, &10_000);
+    client.publish_campaign(&1);
+
+    // Investing before activation fails
+    assert_eq!(client.try_invest(&1, &investor, &1_000, &false), Err(Ok(Error::CampaignInactive)));
+
+    // Activation needs tokens for the full target (1,000 tokens)
+    client.deposit_tokens(&1, &999);
+    let result = client.try_activate_campaign(&1);
+    assert_eq!(result, Err(Ok(Error::InsufficientBalance.into())));
+
+    client.deposit_tokens(&1, &1);
+    assert_eq!(client.get_token_deposit(&1), 1_000);
+    client.activate_campaign(&1);
+    assert_eq!(client.get_stats().active_campaigns, 1);
+
+    client.invest(&1, &investor, &1_000, &false);
+    assert_eq!(client.get_campaign(&1).raised_amount, 1_000);
+
+    let result = client.try_activate_campaign(&1);
+    assert_eq!(result, Err(Ok(Error::CampaignAlreadyActivated.into())));
+}
+
+#[test]
+fn test_sales_reserved_against_campaign_deposit() {
+    let s = Setup::new();
+    let investor = s.investor(100_000);
+
+    // Oversubscription cannot sell past the 1,000 tokens deposited
+    s.create_draft_campaign(1);
+    s.client.set_allow_oversubscription(&1, &true);
+    s.client.publish_campaign(&1);
+    s.client.deposit_tokens(&1, &1_000);
+    s.client.activate_campaign(&1);
+    s.client.invest(&1, &investor, &10_000, &false);
+    assert_eq!(s.client.tokens_remaining(&1), 0);
+    let result = s.client.try_invest(&1, &investor, &100, &false);
+    assert_eq!(result, Err(Ok(Error::InsufficientBalance)));
+
+    // Nor can a later exchange rate that buys more tokens per unit raised
+    s.create_draft_campaign(2);
+    s.client.publish_campaign(&2);
+    s.client.deposit_tokens(&2, &1_000);
+    s.client.activate_campaign(&2);
+    s.client.set_exchange_rate(&2, &20_000_000);
+    s.client.invest(&2, &investor, &5_000, &false);
+    assert_eq!(s.client.get_investment(&2, &investor).tokens_received, 1_000);
+    let result = s.client.try_invest(&2, &investor, &100, &false);
+    assert_eq!(result, Err(Ok(Error::InsufficientBalance)));
+}
+
+#[test]
+fn test_tokens_per_xlm() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+
+    // Price of 10 stroops per token
+    assert_eq!(s.client.get_tokens_per_xlm(&1), 1_000_000);
+}
+
+#[test]
+fn test_remaining_allowance_near_cap() {
+    let s = Setup::new();
+    s.client.create_campaign(
+        &1,
+        &s.company,
+        &s.equity,
+        &10_000,
+        &10,
+        &(s.env.ledger().timestamp() + 30 * DAY),
+        &100,
+        &2_000,
+        &10_000,
+    );
+    s.launch(1);
+    let investor = s.investor(10_000);
+
+    assert_eq!(s.client.get_remaining_allowance(&1, &investor), 2_000);
+
+    s.client.invest(&1, &investor, &1_800, &false);
+    assert_eq!(s.client.get_remaining_allowance(&1, &investor), 200);
+
+    s.client.invest(&1, &investor, &200, &false);
+    assert_eq!(s.client.get_remaining_allowance(&1, &investor), 0);
+
+    s.create_default_campaign(2);
+    assert_eq!(s.client.get_remaining_allowance(&2, &investor), i128::MAX);
+}
+
+#[test]
+fn test_campaigns_by_tag() {
+    let s = Setup::new();
+    let green = symbol_short!("green");
+    let ai = symbol_short!("AI");
+    let seed = symbol_short!("seed");
+
+    for campaign_id in 1..=3 {
+        s.create_draft_campaign(campaign_id);
+    }
+    s.client.set_campaign_tags(&1, &Vec::from_array(&s.env, [green.clone(), seed.clone()]));
+    s.client.set_campaign_tags(&2, &Vec::from_array(&s.env, [ai.clone(), seed.clone()]));
+    s.client.set_campaign_tags(&3, &Vec::from_array(&s.env, [green.clone()]));
+
+    // Re-tagging replaces the old index entries
+    s.client.set_campaign_tags(&3, &Vec::from_array(&s.env, [green.clone(), ai.clone()]));
+    for campaign_id in 1..=3 {
+        s.launch(campaign_id);
+    }
+
+    assert_eq!(s.client.get_campaigns_by_tag(&green, &0, &10), Vec::from_array(&s.env, [1, 3]));
+    assert_eq!(s.client.get_campaigns_by_tag(&ai, &0, &10), Vec::from_array(&s.env, [2, 3]));
+    assert_eq!(s.client.get_campaigns_by_tag(&seed, &0, &10), Vec::from_array(&s.env, [1, 2]));
+    assert_eq!(s.client.get_campaigns_by_tag(&seed, &1, &10), Vec::from_array(&s.env, [2]));
+    assert_eq!(s.client.get_campaigns_by_tag(&seed, &5, &10).len(), 0);
+}
+
+#[test]
+fn test_campaigns_by_tag_hides_drafts() {
+    let s = Setup::new();
+    let green = symbol_short!("green");
+
+    s.create_draft_campaign(1);
+    s.client.set_campaign_tags(&1, &Vec::from_array(&s.env, [green.clone()]));
+    assert_eq!(s.client.get_campaigns_by_tag(&green, &0, &10).len(), 0);
+
+    s.client.publish_campaign(&1);
+    assert_eq!(s.client.get_campaigns_by_tag(&green, &0, &10), Vec::from_array(&s.env, [1]));
+}
+
+#[test]
+fn test_failed_xlm_transfer_leaves_no_state() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    let investor = s.investor(500);
+
+    let result = s.client.try_invest(&1, &investor, &1_000, &false);
+    assert_eq!(result, Err(Ok(Error::TransferFailed)));
+
+    assert_eq!(s.client.get_campaign(&1).raised_amount, 0);
+    assert_eq!(s.client.get_investors(&1).len(), 0);
+    assert!(!s.client.has_invested(&1, &investor));
+    assert_eq!(TokenClient::new(&s.env, &s.xlm).balance(&investor), 500);
+}
+
+#[test]
+fn test_reactivate_within_window() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    let investor = s.investor(10_000);
+    s.client.invest(&1, &investor, &1_000, &false);
+
+    s.client.close_campaign(&1, &s.company);
+    assert_eq!(s.client.get_stats().active_campaigns, 0);
+
+    s.advance(DAY / 2);
+    s.client.reactivate_campaign(&1);
+
+    let campaign = s.client.get_campaign(&1);
+    assert!(campaign.is_active);
+    assert_eq!(campaign.refund_deadline, 0);
+    assert_eq!(s.client.get_stats().active_campaigns, 1);
+    s.client.invest(&1, &investor, &1_000, &false);
+}
+
+#[test]
+fn test_reactivate_rejected_after_window() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+
+    s.client.close_campaign(&1, &s.company);
+    s.advance(DAY + 1);
+
+    let result = s.client.try_reactivate_campaign(&1);
+    assert_eq!(result, Err(Ok(Error::ReactivationWindowPassed.into())));
+}
+
+#[test]
+fn test_reactivate_rejected_after_deadline() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    s.client.set_reactivation_window(&(60 * DAY));
+
+    s.advance(29 * DAY);
+    s.client.close_campaign(&1, &s.company);
+    s.advance(2 * DAY);
+
+    let result = s.client.try_reactivate_campaign(&1);
+    assert_eq!(result, Err(Ok(Error::DeadlinePassed.into())));
+}
+
+#[test]
+fn test_extend_deadline() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    let new_deadline = 1_000 + 45 * DAY;
+
+    s.client.extend_deadline(&1, &new_deadline);
+    assert_eq!(s.client.get_campaign(&1).deadline, new_deadline);
+
+    // Past the original deadline, investing is still open
+    s.advance(31 * DAY);
+    let investor = s.investor(1_000);
+    s.client.invest(&1, &investor, &1_000, &false);
+}
+
+#[test]
+fn test_extend_deadline_rejects_invalid() {
+    let s = Setup::new();
+    s.create_draft_campaign(1);
+
+    let result = s.client.try_extend_deadline(&1, &(1_000 + 40 * DAY));
+    assert_eq!(result, Err(Ok(Error::CampaignInactive)));
+
+    s.launch(1);
+    let earlier = s.client.try_extend_deadline(&1, &(1_000 + 30 * DAY));
+    assert_eq!(earlier, Err(Ok(Error::DeadlineInvalid)));
+
+    // At most twice the original 30 day duration
+    s.client.extend_deadline(&1, &(1_000 + 60 * DAY));
+    let beyond_cap = s.client.try_extend_deadline(&1, &(1_000 + 60 * DAY + 1));
+    assert_eq!(beyond_cap, Err(Ok(Error::DeadlineInvalid)));
+}
+
+#[test]
+fn test_extend_deadline_rejected_once_expired() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    let investor = s.investor(1_000);
+    s.client.invest(&1, &investor, &1_000, &false);
+
+    // Expired below target, so investors can already take their XLM back
+    s.advance(31 * DAY);
+    s.client.claim_refund(&1, &investor);
+
+    let result = s.client.try_extend_deadline(&1, &(1_000 + 45 * DAY));
+    assert_eq!(result, Err(Ok(Error::DeadlineInvalid)));
+    assert_eq!(s.client.get_withdrawable(&1), 0);
+}
+
+#[test]
+fn test_transaction_count_includes_top_ups() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    let investor = s.investor(10_000);
+
+    assert_eq!(s.client.get_transaction_count(&1), 0);
+
+    s.client.invest(&1, &investor, &1_000, &false);
+    s.client.invest(&1, &investor, &1_000, &false);
+
+    assert_eq!(s.client.get_transaction_count(&1), 2);
+    assert_eq!(s.client.get_investor_count(&1), 1);
+}
+
+#[test]
+fn test_progressive_cap_tightens_as_campaign_fills() {
+    let s = Setup::new();
+    s.create_draft_campaign(1);
+    s.client.set_progressive_cap(&1, &4_000, &1_000);
+    s.launch(1);
+
+    assert_eq!(s.client.get_investor_cap(&1), 4_000);
+
+    let early = s.investor(10_000);
+    s.client.invest(&1, &early, &4_000, &false);
+    assert_eq!(s.client.get_investor_cap(&1), 2_800);
+
+    let middle = s.investor(10_000);
+    s.client.invest(&1, &middle, &2_800, &false);
+    assert_eq!(s.client.get_investor_cap(&1), 1_960);
+
+    let late = s.investor(10_000);
+    assert_eq!(s.client.try_invest(&1, &late, &2_000, &true), Err(Ok(Error::InvestmentTooLarge)));
+    s.client.invest(&1, &late, &1_960, &false);
+    assert_eq!(s.client.get_remaining_allowance(&1, &late), 0);
+}
+
+#[test]
+fn test_progressive_cap_respects_max_investment() {
+    let s = Setup::new();
+    s.client.create_campaign(
+        &1,
+        &s.company,
+        &s.equity,
+        &10_000,
+        &10,
+        &(s.env.ledger().timestamp() + 30 * DAY),
+        &100,
+        &2_000,
+        &10_000,
+    );
+    s.client.set_progressive_cap(&1, &4_000, &1_000);
+
+    assert_eq!(s.client.get_investor_cap(&1), 2_000);
+    assert_eq!(
+        s.client.try_set_progressive_cap(&1, &500, &1_000),
+        Err(Ok(Error::InvalidAmount.into()))
+    );
+}
+
+#[test]
+fn test_redeem_burns_tokens_for_escrowed_xlm() {
+    let s = Setup::new();
+    s.create_draft_campaign(1);
+    s.client.set_redeemable(&1, &true);
+    s.launch(1);
+    let investor = s.investor(10_000);
+
+    s.client.invest(&1, &investor, &2_000, &false);
+    s.client.claim_tokens(&1, &investor);
+
+    let token = equity_token::EquityTokenClient::new(&s.env, &s.equity);
+    let supply_before = token.get_company_info().total_supply;
+
+    s.client.redeem(&1, &investor, &50);
+
+    assert_eq!(token.balance_of(&investor), 150);
+    assert_eq!(token.get_company_info().total_supply, supply_before - 50);
+    assert_eq!(TokenClient::new(&s.env, &s.xlm).balance(&investor), 8_500);
+
+    let investment = s.client.get_investment(&1, &investor);
+    assert_eq!(investment.tokens_received, 150);
+    assert_eq!(investment.amount_invested, 1_500);
+    assert_eq!(s.client.get_campaign(&1).redeemed_amount, 500);
+
+    assert_eq!(
+        s.client.try_redeem(&1, &investor, &151),
+        Err(Ok(Error::InsufficientBalance.into()))
+    );
+}
+
+#[test]
+fn test_reissue_receipt_after_redemption() {
+    let s = Setup::new();
+    s.create_draft_campaign(1);
+    s.client.set_redeemable(&1, &true);
+    s.launch(1);
+    let investor = s.investor(10_000);
+
+    s.client.invest(&1, &investor, &2_000, &false);
+    s.client.claim_tokens(&1, &investor);
+    let original = s.client.reissue_receipt(&1, &investor);
+    assert!(s.client.is_receipt_valid(&1, &investor, &original));
+
+    // Redeeming part of the position amends the investment
+    s.client.redeem(&1, &investor, &50);
+    assert!(!s.client.is_receipt_valid(&1, &investor, &original));
+
+    let reissued = s.client.reissue_receipt(&1, &investor);
+    assert_ne!(reissued, original);
+    assert!(s.client.is_receipt_valid(&1, &investor, &reissued));
+
+    let stranger = Address::generate(&s.env);
+    assert_eq!(
+        s.client.try_reissue_receipt(&1, &stranger),
+        Err(Ok(Error::InsufficientBalance.into()))
+    );
+}
+
+#[test]
+fn test_redeem_rejected_when_disabled() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    let investor = s.investor(10_000);
+
+    s.client.invest(&1, &investor, &2_000, &false);
+    s.client.claim_tokens(&1, &investor);
+
+    assert_eq!(
+        s.client.try_redeem(&1, &investor, &50),
+        Err(Ok(Error::RedemptionDisabled.into()))
+    );
+    let token = equity_token::EquityTokenClient::new(&s.env, &s.equity);
+    assert_eq!(token.balance_of(&investor), 200);
+}
+
+#[test]
+fn test_extend_ttl_keeps_campaign_alive() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+
+    let campaign_key = FundraisingContract::get_campaign_key(1);
+    let ttl_of = |key: &(&'static str, u64)| {
+        s.env.as_contract(&s.client.address, || s.env.storage().persistent().get_ttl(key))
+    };
+    let original_ttl = ttl_of(&campaign_key);
+
+    s.client.extend_ttl(&1, &(original_ttl + 100_000));
+    assert_eq!(ttl_of(&campaign_key), original_ttl + 100_000);
+
+    // Past the original expiry the entry is still live, not archived
+    s.env.ledger().with_mut(|l| l.sequence_number += original_ttl + 50_000);
+    assert_eq!(ttl_of(&campaign_key), 50_000);
+    assert_eq!(s.client.get_campaign(&1).target_amount, 10_000);
+}
+
+#[test]
+fn test_invest_auto_extends_ttl() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    let investor = s.investor(10_000);
+
+    s.client.invest(&1, &investor, &1_000, &false);
+
+    let (campaign_ttl, investment_ttl) = s.env.as_contract(&s.client.address, || {
+        let storage = s.env.storage().persistent();
+        (
+            storage.get_ttl(&FundraisingContract::get_campaign_key(1)),
+            storage.get_ttl(&FundraisingContract::get_investment_key(1, &investor)),
+        )
+    });
+    assert_eq!(campaign_ttl, CAMPAIGN_TTL_EXTEND_TO);
+    assert_eq!(investment_ttl, CAMPAIGN_TTL_EXTEND_TO);
+
+    s.env.ledger().with_mut(|l| l.sequence_number += CAMPAIGN_TTL_EXTEND_TO - 1);
+    assert_eq!(s.client.get_campaign(&1).raised_amount, 1_000);
+    assert_eq!(s.client.get_investment(&1, &investor).amount_invested, 1_000);
+}
+
+#[test]
+fn test_active_campaign_ids_filters_inactive() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    s.create_default_campaign(2);
+    s.create_draft_campaign(3);
+    s.create_default_campaign(4);
+    s.client.close_campaign(&2, &s.company);
+
+    let mut expected = Vec::new(&s.env);
+    expected.push_back(1);
+    expected.push_back(4);
+    assert_eq!(s.client.get_active_campaign_ids(&0, &10), expected);
+
+    // Paging walks the registry, not the filtered result
+    let mut first_page = Vec::new(&s.env);
+    first_page.push_back(1);
+    assert_eq!(s.client.get_active_campaign_ids(&0, &2), first_page);
+    let mut second_page = Vec::new(&s.env);
+    second_page.push_back(4);
+    assert_eq!(s.client.get_active_campaign_ids(&2, &2), second_page);
+    assert!(s.client.get_active_campaign_ids(&4, &2).is_empty());
+}
+
+#[test]
+fn test_fill_or_kill_rejects_what_a_partial_fill_accepts() {
+    let s = Setup::new();
+    s.create_draft_campaign(1);
+    s.client.set_progressive_cap(&1, &3_000, &3_000);
+    s.launch(1);
+    let investor = s.investor(10_000);
+    s.client.invest(&1, &investor, &2_000, &false);
+
+    // Only 1,000 of the investor's cap is left
+    assert_eq!(
+        s.client.try_invest(&1, &investor, &1_500, &true),
+        Err(Ok(Error::InvestmentTooLarge))
+    );
+    assert_eq!(s.client.get_investment(&1, &investor).amount_invested, 2_000);
+
+    s.client.invest(&1, &investor, &1_500, &false);
+    let investment = s.client.get_investment(&1, &investor);
+    assert_eq!(investment.amount_invested, 3_000);
+    assert_eq!(investment.tokens_received, 300);
+    assert_eq!(TokenClient::new(&s.env, &s.xlm).balance(&investor), 7_000);
+}
+
+#[test]
+fn test_max_tokens_per_investor() {
+    let s = Setup::new();
+    s.create_draft_campaign(1);
+    s.client.set_max_tokens_per_investor(&1, &300);
+    s.launch(1);
+    let investor = s.investor(10_000);
+
+    s.client.invest(&1, &investor, &2_000, &false);
+
+    let result = s.client.try_invest(&1, &investor, &1_100, &false);
+    assert_eq!(result, Err(Ok(Error::InvestmentTooLarge)));
+
+    s.client.invest(&1, &investor, &1_000, &false);
+    assert_eq!(s.client.get_investment(&1, &investor).tokens_received, 300);
+}
+
+#[test]
+fn test_withdraw_all_skips_campaigns_in_progress() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    s.create_default_campaign(2);
+    let investor = s.investor(20_000);
+
+    s.client.invest(&1, &investor, &10_000, &false);
+    s.client.invest(&2, &investor, &3_000, &false);
+
+    assert_eq!(s.client.withdraw_all(&s.company), 10_000);
+
+    let xlm = TokenClient::new(&s.env, &s.xlm);
+    assert_eq!(xlm.balance(&s.company), 10_000);
+    assert_eq!(s.client.get_campaign(&1).withdrawn_amount, 10_000);
+    assert!(!s.client.get_campaign(&1).is_active);
+
+    let in_progress = s.client.get_campaign(&2);
+    assert_eq!(in_progress.withdrawn_amount, 0);
+    assert!(in_progress.is_active);
+    assert_eq!(xlm.balance(&s.client.address), 3_000);
+}
+
+#[test]
+fn test_exchange_rate_applied_to_issuance() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    let investor = s.investor(10_000);
+
+    assert_eq!(s.client.get_exchange_rate(&1), 10_000_000);
+    s.client.invest(&1, &investor, &1_000, &false);
+    assert_eq!(s.client.get_investment(&1, &investor).tokens_received, 100);
+
+    // 1 raise token = 2.5 XLM
+    s.client.set_exchange_rate(&1, &25_000_000);
+    s.client.invest(&1, &investor, &1_000, &false);
+    assert_eq!(s.client.get_investment(&1, &investor).tokens_received, 350);
+
+    assert_eq!(
+        s.client.try_set_exchange_rate(&1, &0),
+        Err(Ok(Error::InvalidAmount.into()))
+    );
+}
+
+#[test]
+fn test_describe_campaign_matches_getters() {
+    let s = Setup::new();
+    s.create_draft_campaign(1);
+    assert_eq!(s.client.describe_campaign(&1).get(17), Some(0));
+
+    s.launch(1);
+    let investor = s.investor(10_000);
+    s.client.invest(&1, &investor, &2_500, &false);
+    s.client.invest(&1, &investor, &500, &false);
+
+    let campaign = s.client.get_campaign(&1);
+    let packed = s.client.describe_campaign(&1);
+    assert_eq!(packed.len(), 21);
+    assert_eq!(packed.get(0), Some(campaign.target_amount));
+    assert_eq!(packed.get(1), Some(campaign.price_per_token));
+    assert_eq!(packed.get(2), Some(campaign.raised_amount));
+    assert_eq!(packed.get(6), Some(campaign.min_investment));
+    assert_eq!(packed.get(10), Some(campaign.deadline as i128));
+    assert_eq!(packed.get(11), Some(campaign.created_at as i128));
+    assert_eq!(packed.get(12), Some(campaign.activated_at as i128));
+    assert_eq!(packed.get(15), Some(s.client.get_campaign_progress(&1)));
+    assert_eq!(packed.get(16), Some(7_000));
+    assert_eq!(packed.get(17), Some(2));
+    assert_eq!(packed.get(18), Some(s.client.get_investor_count(&1) as i128));
+    assert_eq!(packed.get(19), Some(s.client.get_transaction_count(&1) as i128));
+    assert_eq!(packed.get(20), Some(campaign.soft_cap));
+
+    s.advance(31 * DAY);
+    assert_eq!(s.client.describe_campaign(&1).get(17), Some(4));
+}
+
+#[test]
+fn test_max_investors_blocks_new_investors_only() {
+    let s = Setup::new();
+    s.create_draft_campaign(1);
+    s.client.set_max_investors(&1, &2);
+    s.launch(1);
+    let alice = s.investor(10_000);
+    let bob = s.investor(10_000);
+    let carol = s.investor(10_000);
+
+    s.client.invest(&1, &alice, &1_000, &false);
+    s.client.invest(&1, &bob, &1_000, &false);
+    assert_eq!(
+        s.client.try_invest(&1, &carol, &1_000, &false),
+        Err(Ok(Error::InvestorLimitReached))
+    );
+
+    // Existing investors can still add capital
+    s.client.invest(&1, &alice, &1_000, &false);
+    assert_eq!(s.client.get_investment(&1, &alice).amount_invested, 2_000);
+}
+
+#[test]
+fn test_max_investors_below_min_investors_rejected() {
+    let s = Setup::new();
+    s.create_draft_campaign(1);
+    s.client.set_min_investors(&1, &3);
+
+    let result = s.client.try_set_max_investors(&1, &2);
+    assert_eq!(result, Err(Ok(Error::InvalidAmount.into())));
+}
+
+#[test]
+fn test_exit_allowed_until_min_investors_reached() {
+    let s = Setup::new();
+    s.create_draft_campaign(1);
+    s.client.set_min_investors(&1, &2);
+    s.launch(1);
+    let first = s.investor(10_000);
+    let second = s.investor(10_000);
+    let xlm = TokenClient::new(&s.env, &s.xlm);
+
+    s.client.invest(&1, &first, &1_000, &false);
+    assert!(!s.client.is_locked(&1));
+
+    // Below the threshold the first investor can leave with everything back
+    s.client.exit_investment(&1, &first);
+    assert_eq!(xlm.balance(&first), 10_000);
+    assert_eq!(s.client.get_campaign(&1).raised_amount, 0);
+    assert_eq!(s.client.get_investor_count(&1), 0);
+    assert!(!s.client.has_invested(&1, &first));
+    assert_eq!(s.client.tokens_remaining(&1), CAMPAIGN_DEPOSIT);
+
+    s.client.invest(&1, &first, &1_000, &false);
+    s.client.invest(&1, &second, &1_000, &false);
+    assert!(s.client.is_locked(&1));
+    assert!(s.client.get_campaign(&1).locked);
+
+    let result = s.client.try_exit_investment(&1, &first);
+    assert_eq!(result, Err(Ok(Error::InvestmentLocked.into())));
+    assert_eq!(s.client.get_campaign(&1).raised_amount, 2_000);
+}
+
+#[test]
+fn test_exit_rejected_after_close_or_while_frozen() {
+    let s = Setup::new();
+    s.create_draft_campaign(1);
+    s.client.set_min_investors(&1, &5);
+    s.launch(1);
+    let investor = s.investor(10_000);
+    s.client.invest(&1, &investor, &1_000, &false);
+
+    s.client.set_refunds_frozen(&true);
+    let result = s.client.try_exit_investment(&1, &investor);
+    assert_eq!(result, Err(Ok(Error::RefundsFrozen.into())));
+    s.client.set_refunds_frozen(&false);
+
+    s.client.set_paused(&true);
+    let result = s.client.try_exit_investment(&1, &investor);
+    assert_eq!(result, Err(Ok(Error::Paused.into())));
+    s.client.set_paused(&false);
+
+    // A closed campaign is settled through claim_refund, not exits
+    s.client.close_campaign(&1, &s.company);
+    let result = s.client.try_exit_investment(&1, &investor);
+    assert_eq!(result, Err(Ok(Error::CampaignInactive.into())));
+    assert_eq!(s.client.get_campaign(&1).raised_amount, 1_000);
+}
+
+#[test]
+fn test_campaign_token_info_matches_equity_token() {
+    let s = Setup::new();
+    s.create_draft_campaign(1);
+
+    let info = s.client.get_campaign_token_info(&1);
+    let expected = equity_token::EquityTokenClient::new(&s.env, &s.equity).get_company_info();
+    assert_eq!(info.name, expected.name);
+    assert_eq!(info.symbol, expected.symbol);
+    assert_eq!(info.equity_percent, expected.equity_percent);
+    assert_eq!(info.total_supply, expected.total_supply);
+    assert_eq!(info.owner, expected.owner);
+}
+
+#[test]
+fn test_campaign_fee_override_takes_precedence() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    s.create_default_campaign(2);
+    let investor = s.investor(20_000);
+    let xlm = TokenClient::new(&s.env, &s.xlm);
+
+    s.client.set_fee(&500);
+    s.client.set_campaign_fee(&1, &Some(200));
+
+    s.client.invest(&1, &investor, &10_000, &false);
+    s.client.invest(&2, &investor, &10_000, &false);
+
+    // Override: 2% of 10,000
+    assert_eq!(s.client.get_withdrawable(&1), 9_800);
+    s.client.withdraw_funds(&1);
+    assert_eq!(xlm.balance(&s.admin), 200);
+    assert_eq!(xlm.balance(&s.company), 9_800);
+
+    // Global fallback: 5% of 10,000
+    s.client.withdraw_funds(&2);
+    assert_eq!(xlm.balance(&s.admin), 700);
+    assert_eq!(xlm.balance(&s.company), 19_300);
+}
+
+#[test]
+fn test_campaign_fee_override_capped() {
+    let s = Setup::new();
+    s.create_draft_campaign(1);
+
+    assert_eq!(
+        s.client.try_set_campaign_fee(&1, &Some(1_001)),
+        Err(Ok(Error::InvalidAmount.into()))
+    );
+    assert_eq!(s.client.try_set_fee(&1_001), Err(Ok(Error::InvalidAmount.into())));
+
+    s.client.set_campaign_fee(&1, &Some(1_000));
+    assert_eq!(s.client.get_campaign(&1).fee_bps_override, Some(1_000));
+}
+
+#[test]
+fn test_repair_campaign_resums_investments() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    let first = s.investor(10_000);
+    let second = s.investor(10_000);
+
+    s.client.invest(&1, &first, &1_500, &false);
+    s.client.invest(&1, &second, &2_500, &false);
+    s.client.invest(&1, &first, &500, &false);
+
+    // Corrupt the stored total
+    s.env.as_contract(&s.client.address, || {
+        let key = FundraisingContract::get_campaign_key(1);
+        let mut campaign: Campaign = s.env.storage().persistent().get(&key).unwrap();
+        campaign.raised_amount = -42;
+        s.env.storage().persistent().set(&key, &campaign);
+    });
+
+    assert_eq!(s.client.repair_campaign(&1), 4_500);
+    assert_eq!(s.client.get_campaign(&1).raised_amount, 4_500);
+}
+
+#[test]
+fn test_repair_campaign_keeps_refunds_and_redemptions() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    let alice = s.investor(10_000);
+    let bob = s.investor(10_000);
+
+    s.client.invest(&1, &alice, &2_000, &false);
+    s.client.invest(&1, &bob, &3_000, &false);
+    s.client.close_campaign(&1, &s.company);
+    s.client.claim_refund(&1, &alice);
+
+    // The refunded principal stays in the raise, netted out of escrow by refunded_amount
+    assert_eq!(s.client.repair_campaign(&1), 5_000);
+    assert_eq!(s.client.get_withdrawable(&1), 0);
+    s.client.claim_refund(&1, &bob);
+    assert_eq!(TokenClient::new(&s.env, &s.xlm).balance(&bob), 10_000);
+
+    s.create_draft_campaign(2);
+    s.client.set_redeemable(&2, &true);
+    s.launch(2);
+    s.client.invest(&2, &alice, &2_000, &false);
+    s.client.claim_tokens(&2, &alice);
+    s.client.redeem(&2, &alice, &50);
+
+    // Likewise for redeemed principal and redeemed_amount
+    assert_eq!(s.client.repair_campaign(&2), 2_000);
+    assert_eq!(s.client.get_campaign(&2).redeemed_amount, 500);
+    s.client.redeem(&2, &alice, &150);
+    assert_eq!(TokenClient::new(&s.env, &s.xlm).balance(&alice), 10_000);
+}
+
+#[test]
+fn test_record_raise_overtaken() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    s.create_default_campaign(2);
+    let investor = s.investor(20_000);
+
+    assert_eq!(s.client.get_record_raise(), (0, 0));
+
+    s.client.invest(&1, &investor, &4_000, &false);
+    s.client.invest(&2, &investor, &3_000, &false);
+    assert_eq!(s.client.get_record_raise(), (1, 4_000));
+
+    s.client.invest(&2, &investor, &2_000, &false);
+    assert_eq!(s.client.get_record_raise(), (2, 5_000));
+}
+
+#[test]
+fn test_invest_returns_typed_errors() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    let investor = s.investor(10_000);
+
+    assert_eq!(s.client.try_invest(&1, &investor, &0, &false), Err(Ok(Error::InvalidAmount)));
+    assert_eq!(s.client.try_invest(&9, &investor, &1_000, &false), Err(Ok(Error::CampaignNotFound)));
+    assert_eq!(s.client.try_invest(&1, &investor, &50, &false), Err(Ok(Error::InvestmentTooSmall)));
+
+    s.advance(31 * DAY);
+    assert_eq!(s.client.try_invest(&1, &investor, &1_000, &false), Err(Ok(Error::DeadlinePassed)));
+
+    // A rejected investment leaves no partial state behind
+    assert_eq!(TokenClient::new(&s.env, &s.xlm).balance(&investor), 10_000);
+    assert_eq!(s.client.get_campaign(&1).raised_amount, 0);
+}
+
+#[test]
+fn test_invest_before_initialize() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = FundraisingContractClient::new(&env, &env.register(FundraisingContract, ()));
+
+    let result = client.try_invest(&1, &Address::generate(&env), &1_000, &false);
+    assert_eq!(result, Err(Ok(Error::NotInitialized)));
+}
+
+#[test]
+fn test_confirmed_commitment_issues_tokens() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    let investor = s.investor(10_000);
+
+    s.client.commit_investment(&1, &investor, &5_000);
+    assert_eq!(TokenClient::new(&s.env, &s.xlm).balance(&investor), 5_000);
+    assert_eq!(s.client.get_campaign(&1).raised_amount, 0);
+    assert_eq!(s.client.get_commitment(&1, &investor).unwrap().amount, 5_000);
+
+    s.advance(DAY / 2);
+    s.client.confirm_investment(&1, &investor);
+
+    assert_eq!(s.client.get_campaign(&1).raised_amount, 5_000);
+    assert_eq!(s.client.get_investment(&1, &investor).tokens_received, 500);
+    assert!(s.client.get_commitment(&1, &investor).is_none());
+}
+
+#[test]
+fn test_unconfirmed_commitment_refundable_after_window() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    let investor = s.investor(10_000);
+
+    s.client.commit_investment(&1, &investor, &5_000);
+    s.advance(DAY + 1);
+
+    assert_eq!(
+        s.client.try_confirm_investment(&1, &investor),
+        Err(Ok(Error::CommitmentExpired))
+    );
+
+    s.client.cancel_commitment(&1, &investor);
+    assert_eq!(TokenClient::new(&s.env, &s.xlm).balance(&investor), 10_000);
+    assert_eq!(s.client.get_investment(&1, &investor).tokens_received, 0);
+    assert_eq!(
+        s.client.try_cancel_commitment(&1, &investor),
+        Err(Ok(Error::CommitmentNotFound))
+    );
+}
+
+#[test]
+fn test_failed_campaign_refunds_instead_of_withdrawing() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    let investor = s.investor(10_000);
+    let xlm = TokenClient::new(&s.env, &s.xlm);
+
+    s.client.invest(&1, &investor, &4_000, &false);
+    s.advance(31 * DAY);
+
+    // Past the deadline below target, the company cannot take the funds
+    assert_eq!(s.client.get_withdrawable(&1), 0);
+    assert!(s.client.try_withdraw_funds(&1).is_err());
+
+    s.client.claim_refund(&1, &investor);
+    assert_eq!(xlm.balance(&investor), 10_000);
+
+    let investment = s.client.get_investment(&1, &investor);
+    assert!(investment.refunded);
+    assert_eq!(investment.amount_invested, 0);
+    assert_eq!(s.client.try_claim_refund(&1, &investor), Err(Ok(Error::RefundUnavailable)));
+}
+
+#[test]
+fn test_dilution_after_additional_raise() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    let alice = s.investor(10_000);
+    let bob = s.investor(10_000);
+
+    s.client.invest(&1, &alice, &2_000, &false);
+    s.client.invest(&1, &bob, &2_000, &false);
+
+    // Alice holds 200 of 400 tokens sold: 5,000 bps
+    let tokens_sold = 400;
+    let pre_bps = s.client.get_investment(&1, &alice).tokens_received * 10_000 / tokens_sold;
+    assert_eq!(pre_bps, 5_000);
+
+    // Another 4,000 XLM issues 400 more tokens: she ends with 2,500 bps
+    let dilution = s.client.get_dilution_after(&1, &4_000);
+    assert_eq!(dilution, 5_000);
+    let post_bps = pre_bps * (10_000 - dilution) / 10_000;
+    assert_eq!(post_bps, 200 * 10_000 / (tokens_sold + 400));
+
+    assert_eq!(s.client.get_dilution_after(&1, &0), 0);
+}
+
+/// Live campaign with a 10,000 target and a 4,000 soft cap
+fn create_soft_cap_campaign(s: &Setup, campaign_id: u64) {
+    s.client.create_campaign(
+        &campaign_id,
+        &s.company,
+        &s.equity,
+        &10_000,
+        &10,
+        &(s.env.ledger().timestamp() + 30 * DAY),
+        &100,
+        &0,
+        &4_000,
+    );
+    s.launch(campaign_id);
+}
+
+#[test]
+fn test_withdraw_after_deadline_requires_soft_cap() {
+    let s = Setup::new();
+    create_soft_cap_campaign(&s, 1);
+    create_soft_cap_campaign(&s, 2);
+    let investor = s.investor(20_000);
+
+    s.client.invest(&1, &investor, &5_000, &false);
+    s.client.invest(&2, &investor, &3_000, &false);
+
+    // Soft cap met, but not withdrawable until the deadline
+    assert_eq!(s.client.try_withdraw_funds(&1), Err(Ok(Error::CannotWithdraw)));
+
+    s.advance(31 * DAY);
+    s.client.withdraw_funds(&1);
+    assert_eq!(TokenClient::new(&s.env, &s.xlm).balance(&s.company), 5_000);
+
+    // Soft cap missed: investors refund instead
+    assert_eq!(s.client.try_withdraw_funds(&2), Err(Ok(Error::CannotWithdraw)));
+    s.client.claim_refund(&2, &investor);
+    assert_eq!(TokenClient::new(&s.env, &s.xlm).balance(&investor), 15_000);
+}
+
+#[test]
+fn test_create_campaign_validates_soft_cap() {
+    let s = Setup::new();
+    let deadline = s.env.ledger().timestamp() + 30 * DAY;
+
+    for soft_cap in [0, 10_001] {
+        let result = s.client.try_create_campaign(
+            &1, &s.company, &s.equity, &10_000, &10, &deadline, &100, &0, &soft_cap,
+        );
+        assert_eq!(result, Err(Ok(Error::InvalidAmount.into())));
+    }
+}
+
+#[test]
+fn test_cap_table_emitted_on_successful_close() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    let alice = s.investor(10_000);
+    let bob = s.investor(10_000);
+    let carol = s.investor(10_000);
+
+    s.client.invest(&1, &alice, &2_000, &false);
+    s.client.invest(&1, &bob, &5_000, &false);
+    s.client.invest(&1, &carol, &3_000, &false);
+    s.client.close_campaign(&1, &s.company);
+
+    let topic = Symbol::new(&s.env, "cap_table_finalized_event");
+    let (_, _, data) = s
+        .env
+        .events()
+        .all()
+        .iter()
+        .find(|(_, topics, _)| {
+            Symbol::try_from_val(&s.env, &topics.get(0).unwrap()).ok() == Some(topic.clone())
+        })
+        .expect("cap table event");
+    let data: Map<Symbol, Val> = Map::try_from_val(&s.env, &data).unwrap();
+    let entries: Vec<(Address, i128)> =
+        Vec::try_from_val(&s.env, &data.get(Symbol::new(&s.env, "entries")).unwrap()).unwrap();
+
+    let mut expected = Vec::new(&s.env);
+    expected.push_back((bob, 500));
+    expected.push_back((carol, 300));
+    expected.push_back((alice, 200));
+    assert_eq!(entries, expected);
+}
+
+#[test]
+fn test_ttl_bumped_on_create_and_withdraw() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    let investor = s.investor(20_000);
+    s.client.invest(&1, &investor, &10_000, &false);
+
+    let ttls = || {
+        s.env.as_contract(&s.client.address, || {
+            (
+                s.env.storage().instance().get_ttl(),
+                s.env.storage().persistent().get_ttl(&FundraisingContract::get_campaign_key(1)),
+                s.env
+                    .storage()
+                    .persistent()
+                    .get_ttl(&FundraisingContract::get_investment_key(1, &investor)),
+            )
+        })
+    };
+    let (instance_ttl, campaign_ttl, _) = ttls();
+    assert!(instance_ttl >= INSTANCE_TTL_THRESHOLD);
+    assert_eq!(campaign_ttl, CAMPAIGN_TTL_EXTEND_TO);
+
+    // Once below the threshold, withdrawing bumps the campaign and every investment
+    s.env.ledger().with_mut(|l| {
+        l.sequence_number += CAMPAIGN_TTL_EXTEND_TO - CAMPAIGN_TTL_THRESHOLD + 1
+    });
+    s.client.withdraw_funds(&1);
+
+    let (_, campaign_ttl, investment_ttl) = ttls();
+    assert_eq!(campaign_ttl, CAMPAIGN_TTL_EXTEND_TO);
+    assert_eq!(investment_ttl, CAMPAIGN_TTL_EXTEND_TO);
+}
+
+#[test]
+fn test_invest_batch_is_all_or_nothing() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    s.create_default_campaign(2);
+    let investor = s.investor(10_000);
+    let xlm = TokenClient::new(&s.env, &s.xlm);
+
+    let ids = Vec::from_array(&s.env, [1u64, 2]);
+    s.client.invest_batch(&investor, &ids, &Vec::from_array(&s.env, [1_000i128, 2_000]));
+    assert_eq!(s.client.get_investment(&1, &investor).amount_invested, 1_000);
+    assert_eq!(s.client.get_investment(&2, &investor).amount_invested, 2_000);
+    assert_eq!(xlm.balance(&investor), 7_000);
+
+    // The second leg is below the minimum, so the first must not fill either
+    let result = s.client.try_invest_batch(&investor, &ids, &Vec::from_array(&s.env, [1_000i128, 50]));
+    assert_eq!(result, Err(Ok(Error::InvestmentTooSmall)));
+    assert_eq!(s.client.get_investment(&1, &investor).amount_invested, 1_000);
+    assert_eq!(xlm.balance(&investor), 7_000);
+
+    let result = s.client.try_invest_batch(&investor, &ids, &Vec::from_array(&s.env, [1_000i128]));
+    assert_eq!(result, Err(Ok(Error::InvalidAmount)));
+}
+
+#[test]
+fn test_create_campaign_auto_assigns_sequential_ids() {
+    let s = Setup::new();
+    let deadline = s.env.ledger().timestamp() + 30 * DAY;
+
+    // An explicitly created campaign takes id 2, so auto ids skip it
+    s.create_draft_campaign(2);
+    assert_eq!(s.client.get_next_campaign_id(), 1);
+
+    let first = s.client.create_campaign_auto(
+        &s.company, &s.equity, &10_000, &10, &deadline, &100, &0, &10_000,
+    );
+    let second = s.client.create_campaign_auto(
+        &s.company, &s.equity, &20_000, &10, &deadline, &100, &0, &20_000,
+    );
+
+    assert_eq!(first, 1);
+    assert_eq!(second, 3);
+    assert_eq!(s.client.get_campaign(&first).target_amount, 10_000);
+    assert_eq!(s.client.get_campaign(&second).target_amount, 20_000);
+    assert_eq!(s.client.get_next_campaign_id(), 4);
+}
+
+#[test]
+fn test_claim_tokens_releases_only_unclaimed_remainder() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    let investor = s.investor(10_000);
+    let token = equity_token::EquityTokenClient::new(&s.env, &s.equity);
+
+    // Investing records the entitlement without moving equity tokens
+    s.client.invest(&1, &investor, &2_000, &false);
+    assert_eq!(token.balance_of(&investor), 0);
+    assert_eq!(s.client.get_investment(&1, &investor).tokens_received, 200);
+
+    s.client.claim_tokens(&1, &investor);
+    assert_eq!(token.balance_of(&investor), 200);
+    assert_eq!(s.client.try_claim_tokens(&1, &investor), Err(Ok(Error::NothingToClaim)));
+
+    s.client.invest(&1, &investor, &1_000, &false);
+    s.client.claim_tokens(&1, &investor);
+    assert_eq!(token.balance_of(&investor), 300);
+    assert_eq!(s.client.get_investment(&1, &investor).tokens_claimed, 300);
+}
+
+#[test]
+fn test_claim_limited_to_campaign_deposit() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    let investor = s.investor(10_000);
+    s.client.invest(&1, &investor, &2_000, &false);
+
+    // An entitlement the campaign never reserved can't be paid from the shared balance
+    s.env.as_contract(&s.client.address, || {
+        let key = FundraisingContract::get_investment_key(1, &investor);
+        let mut investment: Investment = s.env.storage().persistent().get(&key).unwrap();
+        investment.tokens_received = CAMPAIGN_DEPOSIT + 1;
+        s.env.storage().persistent().set(&key, &investment);
+    });
+    let result = s.client.try_claim_tokens(&1, &investor);
+    assert_eq!(result, Err(Ok(Error::InsufficientBalance)));
+}
+
+#[test]
+fn test_exempt_escrow_claims_beyond_transfer_limit() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    let token = equity_token::EquityTokenClient::new(&s.env, &s.equity);
+    let alice = s.investor(10_000);
+    let bob = s.investor(10_000);
+
+    s.client.invest(&1, &alice, &1_000, &false);
+    s.client.invest(&1, &bob, &1_000, &false);
+
+    // Escrow payouts to different investors don't share one daily cap
+    token.set_transfer_limit(&150);
+    token.set_limit_exempt(&s.client.address, &true);
+    s.client.claim_tokens(&1, &alice);
+    s.client.claim_tokens(&1, &bob);
+    assert_eq!(token.balance_of(&alice), 100);
+    assert_eq!(token.balance_of(&bob), 100);
+}
+
+
+#[test]
+fn test_private_phase_limits_investing_to_whitelist() {
+    let s = Setup::new();
+    s.create_draft_campaign(1);
+    let insider = s.investor(10_000);
+    let outsider = s.investor(10_000);
+
+    let private_deadline = s.env.ledger().timestamp() + 5 * DAY;
+    s.client
+        .set_private_phase(&1, &private_deadline, &Vec::from_array(&s.env, [insider.clone()]));
+    s.launch(1);
+    assert_eq!(s.client.get_current_phase(&1), PHASE_PRIVATE);
+
+    s.client.invest(&1, &insider, &1_000, &false);
+    assert_eq!(s.client.try_invest(&1, &outsider, &1_000, &false), Err(Ok(Error::NotWhitelisted)));
+
+    s.advance(5 * DAY);
+    assert_eq!(s.client.get_current_phase(&1), PHASE_PUBLIC);
+    s.client.invest(&1, &outsider, &1_000, &false);
+    assert_eq!(s.client.get_campaign(&1).raised_amount, 2_000);
+}
+
+#[test]
+fn test_kyc_whitelist_gates_investing() {
+    let s = Setup::new();
+    s.create_draft_campaign(1);
+    s.client.set_requires_whitelist(&1, &true);
+    s.launch(1);
+    let investor = s.investor(10_000);
+
+    assert_eq!(s.client.try_invest(&1, &investor, &1_000, &false), Err(Ok(Error::NotWhitelisted)));
+
+    s.client.set_investor_whitelisted(&1, &investor, &true);
+    assert!(s.client.is_whitelisted(&1, &investor));
+    s.client.invest(&1, &investor, &1_000, &false);
+
+    s.client.set_investor_whitelisted(&1, &investor, &false);
+    assert!(!s.client.is_whitelisted(&1, &investor));
+    assert_eq!(s.client.try_invest(&1, &investor, &1_000, &false), Err(Ok(Error::NotWhitelisted)));
+}
+
+#[test]
+fn test_activation_counts_only_the_campaigns_own_deposit() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+
+    // A follow-on round on the same token cannot lean on round 1's deposit
+    s.create_draft_campaign(2);
+    s.client.publish_campaign(&2);
+    let result = s.client.try_activate_campaign(&2);
+    assert_eq!(result, Err(Ok(Error::InsufficientBalance.into())));
+
+    // A cheaper first tier needs more tokens: 5,000 at 5 + 5,000 at 10 = 1,500
+    s.create_draft_campaign(3);
+    let mut tiers = Vec::new(&s.env);
+    tiers.push_back(PriceTier { up_to: 5_000, price_per_token: 5 });
+    s.client.set_price_tiers(&3, &tiers);
+    s.client.publish_campaign(&3);
+    s.client.deposit_tokens(&3, &1_499);
+    let result = s.client.try_activate_campaign(&3);
+    assert_eq!(result, Err(Ok(Error::InsufficientBalance.into())));
+    s.client.deposit_tokens(&3, &1);
+    s.client.activate_campaign(&3);
+}
+
+#[test]
+fn test_fee_paid_to_collector_on_withdrawal() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|l| l.timestamp = 1_000);
+
+    let contract_id = env.register(FundraisingContract, ());
+    let client = FundraisingContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    let collector = Address::generate(&env);
+    let xlm = env.register_stellar_asset_contract_v2(admin.clone()).address();
+    let company = Address::generate(&env);
+
+    assert!(client.try_initialize(&admin, &xlm, &1_001, &collector).is_err());
+    client.initialize(&admin, &xlm, &250, &collector);
+    assert_eq!(client.get_fee(), 250);
+    assert_eq!(client.get_fee_collector(), collector);
+
+    let equity = register_equity_token(&env, &company);
+    client.create_campaign(&1, &company, &equity, &10_000, &10, &(30 * DAY), &100, &0, &10_000);
+    client.publish_campaign(&1);
+    client.deposit_tokens(&1, &1_000);
+    client.activate_campaign(&1);
+
+    let investor = Address::generate(&env);
+    StellarAssetClient::new(&env, &xlm).mint(&investor, &10_000);
+    client.invest(&1, &investor, &10_000, &false);
+    client.withdraw_funds(&1);
+
+    let topic = Symbol::new(&env, "fee_collected_event");
+    let (_, _, data) = env
+        .events()
+        .all()
+        .iter()
+        .find(|(_, topics, _)| {
+            Symbol::try_from_val(&env, &topics.get(0).unwrap()).ok() == Some(topic.clone())
+        })
+        .expect("fee collected event");
+    let data: Map<Symbol, Val> = Map::try_from_val(&env, &data).unwrap();
+    assert_eq!(i128::try_from_val(&env, &data.get(Symbol::new(&env, "fee")).unwrap()), Ok(250));
+
+    // 2.5% of 10,000 goes to the collector, not the admin
+    let xlm_client = TokenClient::new(&env, &xlm);
+    assert_eq!(xlm_client.balance(&collector), 250);
+    assert_eq!(xlm_client.balance(&admin), 0);
+    assert_eq!(xlm_client.balance(&company), 9_750);
+}
+
+#[test]
+fn test_verify_token_distribution_detects_over_issuance() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    let alice = s.investor(10_000);
+    let bob = s.investor(10_000);
+
+    s.client.invest(&1, &alice, &2_000, &false);
+    s.client.invest(&1, &bob, &1_000, &false);
+    s.client.claim_tokens(&1, &alice);
+    s.client.claim_tokens(&1, &bob);
+    assert!(s.client.verify_token_distribution(&1));
+
+    // Simulate a record claiming more tokens than were ever transferred
+    s.env.as_contract(&s.client.address, || {
+        let key = FundraisingContract::get_investment_key(1, &alice);
+        let mut investment: Investment = s.env.storage().persistent().get(&key).unwrap();
+        investment.tokens_claimed += 50;
+        s.env.storage().persistent().set(&key, &investment);
+    });
+    assert!(!s.client.verify_token_distribution(&1));
+}
+
+#[test]
+fn test_issuance_overflow_rejected() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    let investor = s.investor(10_000);
+
+    // An extreme exchange rate would wrap the token calculation
+    s.client.set_exchange_rate(&1, &i128::MAX);
+    assert_eq!(s.client.try_invest(&1, &investor, &1_000, &false), Err(Ok(Error::Overflow)));
+    assert_eq!(s.client.get_campaign(&1).raised_amount, 0);
+    assert_eq!(TokenClient::new(&s.env, &s.xlm).balance(&investor), 10_000);
+}
+
+#[test]
+fn test_transfer_admin_rotates_authority() {
+    let s = Setup::new();
+    let new_admin = Address::generate(&s.env);
+    assert_eq!(s.client.get_admin(), s.admin);
+
+    s.client.transfer_admin(&new_admin);
+    assert_eq!(s.client.get_admin(), new_admin);
+
+    // Admin-only calls now require the new admin's signature
+    s.client.set_fee(&100);
+    assert_eq!(s.env.auths()[0].0, new_admin);
+}
+
+#[test]
+fn test_auto_withdraw_on_target_crossing() {
+    let s = Setup::new();
+    s.create_draft_campaign(1);
+    s.client.set_auto_withdraw(&1, &true);
+    s.launch(1);
+    s.client.set_fee(&100);
+    let alice = s.investor(10_000);
+    let bob = s.investor(10_000);
+    let xlm = TokenClient::new(&s.env, &s.xlm);
+
+    s.client.invest(&1, &alice, &6_000, &false);
+    assert!(s.client.get_campaign(&1).is_active);
+    assert_eq!(xlm.balance(&s.company), 0);
+
+    // The investment that reaches the target releases funds and closes the campaign
+    s.client.invest(&1, &bob, &4_000, &false);
+    let campaign = s.client.get_campaign(&1);
+    assert!(!campaign.is_active);
+    assert_eq!(campaign.closed_at, s.env.ledger().timestamp());
+    assert_eq!(campaign.withdrawn_amount, 10_000);
+    assert_eq!(xlm.balance(&s.company), 9_900);
+    assert_eq!(xlm.balance(&s.admin), 100);
+    assert_eq!(s.client.get_stats().active_campaigns, 0);
+}
+
+#[test]
+fn test_obligations_across_succeeded_and_failed_campaigns() {
+    let s = Setup::new();
+    create_soft_cap_campaign(&s, 1);
+    create_soft_cap_campaign(&s, 2);
+    let alice = s.investor(20_000);
+    let bob = s.investor(20_000);
+
+    s.client.invest(&1, &alice, &5_000, &false);
+    s.client.invest(&2, &bob, &3_000, &false);
+    s.client.claim_tokens(&1, &alice);
+    s.advance(31 * DAY);
+
+    // Campaign 1 met its soft cap and is owed to the company; campaign 2 is owed back
+    // to its investor, whose 300 unclaimed tokens at price 10 are still outstanding
+    assert_eq!(s.client.get_obligations(), (3_000, 5_000, 3_000));
+
+    s.client.withdraw_funds(&1);
+    s.client.claim_refund(&2, &bob);
+    assert_eq!(s.client.get_obligations(), (0, 0, 0));
+}
+
+#[test]
+fn test_top_campaign_investors_in_descending_order() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    let alice = s.investor(10_000);
+    let bob = s.investor(10_000);
+    let carol = s.investor(10_000);
+
+    s.client.invest(&1, &alice, &1_000, &false);
+    s.client.invest(&1, &bob, &3_000, &false);
+    s.client.invest(&1, &carol, &2_000, &false);
+
+    let top = s.client.get_top_campaign_investors(&1, &2);
+    let mut expected = Vec::new(&s.env);
+    expected.push_back((bob, 3_000, 300));
+    expected.push_back((carol, 2_000, 200));
+    assert_eq!(top, expected);
+}
+
+#[test]
+fn test_pause_blocks_investing_and_withdrawals() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    let investor = s.investor(20_000);
+    s.client.invest(&1, &investor, &10_000, &false);
+
+    s.client.set_paused(&true);
+    assert!(s.client.is_paused());
+    assert_eq!(s.client.try_invest(&1, &investor, &1_000, &false), Err(Ok(Error::Paused)));
+    assert_eq!(
+        s.client.try_invest_batch(
+            &investor,
+            &Vec::from_array(&s.env, [1u64]),
+            &Vec::from_array(&s.env, [1_000i128])
+        ),
+        Err(Ok(Error::Paused))
+    );
+    assert_eq!(s.client.try_withdraw_funds(&1), Err(Ok(Error::Paused)));
+
+    // Queries still work while paused
+    assert_eq!(s.client.get_withdrawable(&1), 10_000);
+
+    s.client.set_paused(&false);
+    s.client.withdraw_funds(&1);
+    assert_eq!(TokenClient::new(&s.env, &s.xlm).balance(&s.company), 10_000);
+}
+
+#[test]
+fn test_reentrant_calls_rejected_while_locked() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    let investor = s.investor(20_000);
+
+    // Lock is released after each call, so back-to-back calls work
+    s.client.invest(&1, &investor, &1_000, &false);
+    s.client.invest(&1, &investor, &1_000, &false);
+
+    // Simulate a token contract calling back in while an outer call holds the lock
+    s.env.as_contract(&s.client.address, || {
+        s.env.storage().instance().set(&KEY_LOCK, &true);
+    });
+    assert_eq!(s.client.try_invest(&1, &investor, &1_000, &false), Err(Ok(Error::Reentrancy)));
+    assert_eq!(s.client.try_claim_tokens(&1, &investor), Err(Ok(Error::Reentrancy)));
+    assert_eq!(s.client.try_withdraw_funds(&1), Err(Ok(Error::Reentrancy)));
+    assert_eq!(s.client.try_claim_refund(&1, &investor), Err(Ok(Error::Reentrancy)));
+    assert_eq!(
+        s.client.try_invest_batch(
+            &investor,
+            &Vec::from_array(&s.env, [1u64]),
+            &Vec::from_array(&s.env, [1_000i128])
+        ),
+        Err(Ok(Error::Reentrancy))
+    );
+    assert_eq!(s.client.try_commit_investment(&1, &investor, &1_000), Err(Ok(Error::Reentrancy)));
+    assert_eq!(s.client.try_confirm_investment(&1, &investor), Err(Ok(Error::Reentrancy)));
+    assert_eq!(s.client.try_cancel_commitment(&1, &investor), Err(Ok(Error::Reentrancy)));
+
+    let reentrancy = Err(Ok(Error::Reentrancy.into()));
+    assert_eq!(s.client.try_withdraw_all(&s.company), Err(Ok(Error::Reentrancy.into())));
+    assert_eq!(s.client.try_close_campaign(&1, &s.company), reentrancy);
+    assert_eq!(s.client.try_sweep_unclaimed(&1), reentrancy);
+    assert_eq!(s.client.try_redeem(&1, &investor, &10), reentrancy);
+    assert_eq!(s.client.try_exit_investment(&1, &investor), reentrancy);
+    assert_eq!(s.client.try_deposit_tokens(&1, &100), reentrancy);
+
+    s.env.as_contract(&s.client.address, || {
+        s.env.storage().instance().remove(&KEY_LOCK);
+    });
+    s.client.invest(&1, &investor, &1_000, &false);
+}
+
+#[test]
+fn test_refunds_frozen_until_unfrozen() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    let investor = s.investor(2_000);
+    s.client.invest(&1, &investor, &2_000, &false);
+    s.client.close_campaign(&1, &s.company);
+
+    s.client.set_refunds_frozen(&true);
+    assert!(s.client.is_refunds_frozen());
+    assert_eq!(s.client.try_claim_refund(&1, &investor), Err(Ok(Error::RefundsFrozen)));
+
+    // The general pause is unaffected
+    assert!(!s.client.is_paused());
+
+    s.client.set_refunds_frozen(&false);
+    s.client.claim_refund(&1, &investor);
+    assert_eq!(TokenClient::new(&s.env, &s.xlm).balance(&investor), 2_000);
+}
+
+#[test]
+fn test_payment_token_must_be_allowed() {
+    let s = Setup::new();
+    s.create_draft_campaign(1);
+    let usdc = s.env.register_stellar_asset_contract_v2(s.admin.clone()).address();
+
+    assert!(s.client.is_raise_token_allowed(&s.xlm));
+    assert!(!s.client.is_raise_token_allowed(&usdc));
+    assert_eq!(
+        s.client.try_set_payment_token(&1, &Some(usdc.clone())),
+        Err(Ok(Error::RaiseTokenNotAllowed.into()))
+    );
+
+    s.client.set_raise_token_allowed(&usdc, &true);
+    s.client.set_payment_token(&1, &Some(usdc.clone()));
+    assert_eq!(s.client.get_campaign(&1).payment_token, Some(usdc.clone()));
+
+    s.client.set_raise_token_allowed(&usdc, &false);
+    assert!(!s.client.is_raise_token_allowed(&usdc));
+
+    // A token delisted after it was chosen can never go live
+    s.client.publish_campaign(&1);
+    s.client.deposit_tokens(&1, &CAMPAIGN_DEPOSIT);
+    assert_eq!(
+        s.client.try_activate_campaign(&1),
+        Err(Ok(Error::RaiseTokenNotAllowed.into()))
+    );
+}
+
+#[test]
+fn test_campaign_raises_in_its_payment_token() {
+    let s = Setup::new();
+    let usdc = s.env.register_stellar_asset_contract_v2(s.admin.clone()).address();
+    s.client.set_raise_token_allowed(&usdc, &true);
+    s.create_draft_campaign(1);
+    s.client.set_payment_token(&1, &Some(usdc.clone()));
+    s.launch(1);
+
+    let investor = Address::generate(&s.env);
+    StellarAssetClient::new(&s.env, &usdc).mint(&investor, &10_000);
+    let usdc_client = TokenClient::new(&s.env, &usdc);
+    let xlm = TokenClient::new(&s.env, &s.xlm);
+
+    s.client.invest(&1, &investor, &10_000, &false);
+    assert_eq!(usdc_client.balance(&investor), 0);
+    assert_eq!(usdc_client.balance(&s.client.address), 10_000);
+
+    s.client.withdraw_funds(&1);
+    assert_eq!(usdc_client.balance(&s.company), 10_000);
+    assert_eq!(xlm.balance(&s.company), 0);
+}
+
+#[test]
+fn test_refund_paid_in_payment_token() {
+    let s = Setup::new();
+    let usdc = s.env.register_stellar_asset_contract_v2(s.admin.clone()).address();
+    s.client.set_raise_token_allowed(&usdc, &true);
+    s.create_draft_campaign(1);
+    s.client.set_payment_token(&1, &Some(usdc.clone()));
+    s.launch(1);
+
+    let investor = Address::generate(&s.env);
+    StellarAssetClient::new(&s.env, &usdc).mint(&investor, &10_000);
+
+    s.client.invest(&1, &investor, &2_000, &false);
+    s.client.close_campaign(&1, &s.company);
+    s.client.claim_refund(&1, &investor);
+    assert_eq!(TokenClient::new(&s.env, &usdc).balance(&investor), 10_000);
+}
+
+#[test]
+fn test_utilization_after_partial_raise() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    let investor = s.investor(10_000);
+
+    s.client.invest(&1, &investor, &4_000, &false);
+    // Tokens stay in the contract until claimed
+    assert_eq!(s.client.get_utilization(&1), (4_000, 0));
+
+    // 400 of the 100,000 deposited tokens = 40 bps
+    s.client.claim_tokens(&1, &investor);
+    assert_eq!(s.client.get_utilization(&1), (4_000, 40));
+}
+
+#[test]
+fn test_price_discrepancy_against_token_price() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    assert_eq!(s.client.get_price_discrepancy(&1), 0);
+
+    // Equity token prices at 10, the campaign sells at 8
+    s.client.create_campaign(
+        &2,
+        &s.company,
+        &s.equity,
+        &10_000,
+        &8,
+        &(s.env.ledger().timestamp() + 30 * DAY),
+        &100,
+        &0,
+        &10_000,
+    );
+    assert_eq!(s.client.get_price_discrepancy(&2), 2_500);
+}
+
+#[test]
+fn test_investors_paged() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    let alice = s.investor(1_000);
+    let bob = s.investor(1_000);
+    let carol = s.investor(1_000);
+    s.client.invest(&1, &alice, &1_000, &false);
+    s.client.invest(&1, &bob, &1_000, &false);
+    s.client.invest(&1, &carol, &1_000, &false);
+    assert_eq!(s.client.get_investor_count(&1), 3);
+
+    assert_eq!(
+        s.client.get_investors_paged(&1, &0, &2),
+        Vec::from_array(&s.env, [alice, bob])
+    );
+    assert_eq!(
+        s.client.get_investors_paged(&1, &2, &2),
+        Vec::from_array(&s.env, [carol])
+    );
+    assert_eq!(s.client.get_investors_paged(&1, &3, &2).len(), 0);
+}
+
+#[test]
+fn test_campaign_stats_track_investments() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    let stats = s.client.get_campaign_stats(&1);
+    assert_eq!(
+        (stats.investor_count, stats.raised_amount, stats.avg_investment, stats.largest_investment),
+        (0, 0, 0, 0)
+    );
+
+    let alice = s.investor(10_000);
+    let bob = s.investor(10_000);
+    s.client.invest(&1, &alice, &1_000, &false);
+    s.client.invest(&1, &bob, &3_000, &false);
+    s.client.invest(&1, &alice, &2_000, &false);
+
+    let stats = s.client.get_campaign_stats(&1);
+    assert_eq!(stats.investor_count, 2);
+    assert_eq!(stats.raised_amount, 6_000);
+    assert_eq!(stats.avg_investment, 3_000);
+    assert_eq!(stats.largest_investment, 3_000);
+}
+
+#[test]
+fn test_stats_history_tracks_total_raised() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    s.create_default_campaign(2);
+    let investor = s.investor(20_000);
+    assert_eq!(s.client.get_stats_history().len(), 0);
+
+    s.client.invest(&1, &investor, &2_000, &false);
+    s.client.close_campaign(&1, &s.company);
+
+    s.client.invest(&2, &investor, &10_000, &false);
+    s.client.withdraw_funds(&2);
+
+    let history = s.client.get_stats_history();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history.get(0).unwrap().total_raised, 2_000);
+    assert_eq!(history.get(0).unwrap().active_campaigns, 1);
+    assert_eq!(history.get(1).unwrap().total_raised, 12_000);
+    assert_eq!(history.get(1).unwrap().active_campaigns, 0);
+}
+
+#[test]
+fn test_close_converts_residual_into_bonus_tokens() {
+    let s = Setup::new();
+    s.create_draft_campaign(1);
+    s.client.set_auto_convert_residual(&1, &true);
+    // A premium first tier leaves XLM that the base price did not allocate to tokens
+    let mut tiers = Vec::new(&s.env);
+    tiers.push_back(PriceTier { up_to: 5_000, price_per_token: 20 });
+    s.client.set_price_tiers(&1, &tiers);
+    s.launch(1);
+    let alice = s.investor(10_000);
+    let bob = s.investor(10_000);
+
+    s.client.invest(&1, &alice, &4_000, &false);
+    s.client.invest(&1, &bob, &6_000, &false);
+    assert_eq!(s.client.get_investment(&1, &alice).tokens_received, 200);
+    assert_eq!(s.client.get_investment(&1, &bob).tokens_received, 550);
+    s.client.close_campaign(&1, &s.company);
+
+    // 10,000 raised - 750 tokens * 10 = 2,500 residual = 250 bonus tokens, split 40/60
+    let alice_tokens = s.client.get_investment(&1, &alice).tokens_received;
+    let bob_tokens = s.client.get_investment(&1, &bob).tokens_received;
+    assert_eq!(alice_tokens, 300);
+    assert_eq!(bob_tokens, 700);
+
+    // Tokens at the base price account for exactly what was raised, which the company gets
+    let campaign = s.client.get_campaign(&1);
+    assert!(!campaign.is_active);
+    assert_eq!((alice_tokens + bob_tokens) * campaign.price_per_token, campaign.raised_amount);
+    assert_eq!(campaign.withdrawn_amount, campaign.raised_amount);
+    assert_eq!(TokenClient::new(&s.env, &s.xlm).balance(&s.company), campaign.raised_amount);
+    assert_eq!(TokenClient::new(&s.env, &s.xlm).balance(&s.client.address), 0);
+
+    s.client.claim_tokens(&1, &alice);
+    let token = equity_token::EquityTokenClient::new(&s.env, &s.equity);
+    assert_eq!(token.balance_of(&alice), 300);
+}
+
+#[test]
+fn test_residual_conversion_limited_to_campaign_deposit() {
+    let s = Setup::new();
+    s.create_draft_campaign(1);
+    s.client.set_auto_convert_residual(&1, &true);
+    let mut tiers = Vec::new(&s.env);
+    tiers.push_back(PriceTier { up_to: 5_000, price_per_token: 20 });
+    s.client.set_price_tiers(&1, &tiers);
+    s.client.publish_campaign(&1);
+    s.client.deposit_tokens(&1, &750);
+    s.client.activate_campaign(&1);
+
+    // Another round's deposit in the same token is not available for bonus tokens
+    s.create_default_campaign(2);
+    let alice = s.investor(10_000);
+    s.client.invest(&1, &alice, &10_000, &false);
+    assert_eq!(s.client.tokens_remaining(&1), 0);
+
+    let result = s.client.try_close_campaign(&1, &s.company);
+    assert_eq!(result, Err(Ok(Error::InsufficientBalance.into())));
+    assert_eq!(s.client.tokens_remaining(&2), CAMPAIGN_DEPOSIT);
+}
+
+#[test]
+fn test_close_with_fully_allocated_escrow_adds_no_bonus() {
+    let s = Setup::new();
+    s.create_draft_campaign(1);
+    s.client.set_auto_convert_residual(&1, &true);
+    s.launch(1);
+    let alice = s.investor(10_000);
+    let bob = s.investor(10_000);
+
+    s.client.invest(&1, &alice, &6_000, &false);
+    s.client.invest(&1, &bob, &4_000, &false);
+    s.client.close_campaign(&1, &s.company);
+
+    // Every XLM already bought tokens at the base price, so nothing is converted
+    assert_eq!(s.client.get_investment(&1, &alice).tokens_received, 600);
+    assert_eq!(s.client.get_investment(&1, &bob).tokens_received, 400);
+    assert_eq!(TokenClient::new(&s.env, &s.xlm).balance(&s.company), 10_000);
+}
+
+#[test]
+fn test_close_without_success_does_not_convert() {
+    let s = Setup::new();
+    s.create_draft_campaign(1);
+    s.client.set_auto_convert_residual(&1, &true);
+    s.launch(1);
+    let investor = s.investor(10_000);
+
+    s.client.invest(&1, &investor, &2_000, &false);
+    s.client.close_campaign(&1, &s.company);
+
+    assert_eq!(s.client.get_investment(&1, &investor).tokens_received, 200);
+    assert_eq!(s.client.get_campaign(&1).withdrawn_amount, 0);
+}
+
+#[test]
+fn test_pnl_at_market_price() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    let investor = s.investor(10_000);
+    s.client.invest(&1, &investor, &2_000, &false);
+
+    assert_eq!(s.client.get_pnl(&1, &investor, &None), 0);
+    assert_eq!(s.client.get_pnl(&1, &investor, &Some(15)), 1_000);
+    assert_eq!(s.client.get_pnl(&1, &investor, &Some(7)), -600);
+
+    let result = s.client.try_get_pnl(&1, &investor, &Some(i128::MAX));
+    assert_eq!(result, Err(Ok(Error::Overflow.into())));
+}
+
+#[test]
+fn test_upgrade_replaces_contract_code() {
+    let s = Setup::new();
+    let new_wasm_hash = s.env.deployer().upload_contract_wasm(Bytes::from_slice(&s.env, EMPTY_WASM));
+    assert_ne!(wasm_hash_of(&s.env, &s.client.address), new_wasm_hash.to_array());
+
+    s.client.upgrade(&new_wasm_hash);
+    assert_eq!(s.env.auths()[0].0, s.admin);
+
+    let topic = Symbol::new(&s.env, "upgraded_event");
+    assert!(s
+        .env
+        .events()
+        .all()
+        .iter()
+        .any(|(_, topics, _)| Symbol::try_from_val(&s.env, &topics.get(0).unwrap()).ok() == Some(topic.clone())));
+    assert_eq!(wasm_hash_of(&s.env, &s.client.address), new_wasm_hash.to_array());
+}
+
+#[test]
+fn test_campaign_lineage_follows_parent_chain() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    s.create_draft_campaign(2);
+    s.client.set_parent_campaign(&2, &Some(1));
+
+    assert_eq!(s.client.get_campaign(&2).parent_campaign_id, Some(1));
+    assert_eq!(s.client.get_campaign_lineage(&2), Vec::from_array(&s.env, [1u64, 2]));
+    assert_eq!(s.client.get_campaign_lineage(&1), Vec::from_array(&s.env, [1u64]));
+}
+
+#[test]
+fn test_parent_campaign_rejects_loops_and_missing_parent() {
+    let s = Setup::new();
+    s.create_draft_campaign(1);
+    s.create_draft_campaign(2);
+    s.client.set_parent_campaign(&2, &Some(1));
+
+    assert_eq!(
+        s.client.try_set_parent_campaign(&1, &Some(2)),
+        Err(Ok(Error::InvalidParentCampaign.into()))
+    );
+    assert_eq!(
+        s.client.try_set_parent_campaign(&1, &Some(1)),
+        Err(Ok(Error::InvalidParentCampaign.into()))
+    );
+    assert_eq!(
+        s.client.try_set_parent_campaign(&1, &Some(9)),
+        Err(Ok(Error::InvalidParentCampaign.into()))
+    );
+}
+
+#[test]
+fn test_hard_cap_trims_last_investment_to_target() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    let alice = s.investor(10_000);
+    let bob = s.investor(10_000);
+
+    s.client.invest(&1, &alice, &7_000, &false);
+    s.client.invest(&1, &bob, &5_000, &false);
+
+    let topic = Symbol::new(&s.env, "invested_event");
+    let (_, _, data) = s
+        .env
+        .events()
+        .all()
+        .iter()
+        .find(|(_, topics, _)| {
+            Symbol::try_from_val(&s.env, &topics.get(0).unwrap()).ok() == Some(topic.clone())
+        })
+        .expect("invested event");
+    let data: Map<Symbol, Val> = Map::try_from_val(&s.env, &data).unwrap();
+    let amount = i128::try_from_val(&s.env, &data.get(Symbol::new(&s.env, "amount")).unwrap()).unwrap();
+    assert_eq!(amount, 3_000);
+
+    // Only the 3,000 left to the target is taken from Bob
+    let investment = s.client.get_investment(&1, &bob);
+    assert_eq!(investment.amount_invested, 3_000);
+    assert_eq!(investment.tokens_received, 300);
+    assert_eq!(s.client.get_campaign(&1).raised_amount, 10_000);
+    assert_eq!(TokenClient::new(&s.env, &s.xlm).balance(&bob), 7_000);
+
+    assert_eq!(
+        s.client.try_invest(&1, &bob, &1_000, &false),
+        Err(Ok(Error::CampaignInactive))
+    );
+}
+
+#[test]
+fn test_oversubscription_allowed_when_enabled() {
+    let s = Setup::new();
+    s.create_draft_campaign(1);
+    s.client.set_allow_oversubscription(&1, &true);
+    s.launch(1);
+    let investor = s.investor(20_000);
+
+    s.client.invest(&1, &investor, &12_000, &false);
+    assert_eq!(s.client.get_campaign(&1).raised_amount, 12_000);
+}
+
+#[test]
+fn test_claim_tokens_follows_vesting_schedule() {
+    let s = Setup::new();
+    s.create_draft_campaign(1);
+    // 10 day cliff, fully vested after 100 days
+    s.client.set_vesting_schedule(
+        &1,
+        &VestingSchedule {
+            cliff: 10 * DAY,
+            duration: 100 * DAY,
+            start: s.env.ledger().timestamp(),
+        },
+    );
+    s.launch(1);
+    let investor = s.investor(10_000);
+    let token = equity_token::EquityTokenClient::new(&s.env, &s.equity);
+    s.client.invest(&1, &investor, &1_000, &false);
+
+    // Nothing before the cliff
+    s.advance(9 * DAY);
+    assert_eq!(s.client.vested_amount(&1, &investor), 0);
+    assert_eq!(s.client.try_claim_tokens(&1, &investor), Err(Ok(Error::NothingToClaim)));
+
+    s.advance(16 * DAY);
+    assert_eq!(s.client.vested_amount(&1, &investor), 25);
+    s.client.claim_tokens(&1, &investor);
+    assert_eq!(token.balance_of(&investor), 25);
+
+    s.advance(100 * DAY);
+    assert_eq!(s.client.vested_amount(&1, &investor), 100);
+    s.client.claim_tokens(&1, &investor);
+    assert_eq!(token.balance_of(&investor), 100);
+}
+
+#[test]
+fn test_invested_event_indexes_campaign_and_investor() {
+    let s = Setup::new();
+    s.create_default_campaign(1);
+    let investor = s.investor(10_000);
+    s.client.invest(&1, &investor, &1_000, &false);
+
+    let topic = Symbol::new(&s.env, "invested_event");
+    let (_, topics, data) = s
+        .env
+        .events()
+        .all()
+        .iter()
+        .find(|(_, topics, _)| {
+            Symbol::try_from_val(&s.env, &topics.get(0).unwrap()).ok() == Some(topic.clone())
+        })
+        .expect("invested event");
+    assert_eq!(topics.len(), 3);
+    assert_eq!(u64::try_from_val(&s.env, &topics.get(1).unwrap()).unwrap(), 1);
+    assert_eq!(Address::try_from_val(&s.env, &topics.get(2).unwrap()).unwrap(), investor);
+
+    // Amounts stay in the data body
+    let data: Map<Symbol, Val> = Map::try_from_val(&s.env, &data).unwrap();
+    assert!(data.contains_key(Symbol::new(&s.env, "amount")));
+    assert!(!data.contains_key(Symbol::new(&s.env, "investor")));
+}